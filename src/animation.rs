@@ -0,0 +1,171 @@
+// Copyright (C) 2023  Brynley Llewellyn-Roux and Aryan Jassal
+//
+// This file is part of chess.
+//
+// chess is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published
+// by the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// chess is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A module for the animation plugin.
+
+use crate::{board::*, drag_and_drop::Selected, orientation::Orientation};
+use bevy::prelude::*;
+
+/// A plugin implementing animated movement and captures of pieces.
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AnimationSettings>()
+            .add_systems(Update, (animate_moves, animate_captures));
+    }
+}
+
+/// An enum representing how quickly pieces are animated.
+///
+/// # Examples
+///
+/// ```
+/// use chess::animation::AnimationSpeed;
+///
+/// let speed = AnimationSpeed::Normal;
+/// assert_eq!(speed.next(), AnimationSpeed::Fast);
+/// println!("{} seconds", speed.duration());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AnimationSpeed {
+    /// Pieces move instantly.
+    Off,
+    /// A short animation.
+    Fast,
+    /// The default animation.
+    #[default]
+    Normal,
+    /// A long animation.
+    Slow,
+}
+
+impl AnimationSpeed {
+    /// Returns the duration of an animation in seconds.
+    pub fn duration(&self) -> f32 {
+        match self {
+            AnimationSpeed::Off => 0.0,
+            AnimationSpeed::Fast => 0.1,
+            AnimationSpeed::Normal => 0.2,
+            AnimationSpeed::Slow => 0.4,
+        }
+    }
+
+    /// Returns the speed after this one, going from off to slow, normal, fast and back to off.
+    pub fn next(&self) -> Self {
+        match self {
+            AnimationSpeed::Off => AnimationSpeed::Slow,
+            AnimationSpeed::Fast => AnimationSpeed::Off,
+            AnimationSpeed::Normal => AnimationSpeed::Fast,
+            AnimationSpeed::Slow => AnimationSpeed::Normal,
+        }
+    }
+}
+
+/// A resource containing the animation settings.
+#[derive(Default, Resource)]
+pub struct AnimationSettings {
+    /// How quickly pieces are animated.
+    pub speed: AnimationSpeed,
+}
+
+/// A component for a piece sprite sliding towards its `Position`.
+#[derive(Component)]
+pub struct Tween {
+    start: Vec2,
+    timer: Timer,
+}
+
+impl Tween {
+    /// Creates a new tween starting from the given translation.
+    pub fn new(start: Vec2, settings: &AnimationSettings) -> Self {
+        Self {
+            start,
+            timer: Timer::from_seconds(settings.speed.duration(), TimerMode::Once),
+        }
+    }
+}
+
+/// A component for a captured piece sprite fading out before it is despawned.
+#[derive(Component)]
+pub struct FadeOut(Timer);
+
+impl FadeOut {
+    /// Creates a new fade out.
+    pub fn new(settings: &AnimationSettings) -> Self {
+        Self(Timer::from_seconds(
+            settings.speed.duration(),
+            TimerMode::Once,
+        ))
+    }
+}
+
+fn animate_moves(
+    time: Res<Time>,
+    board: Res<Board>,
+    orientation: Res<Orientation>,
+    mut commands: Commands,
+    mut tweens: Query<(
+        Entity,
+        &mut Transform,
+        &mut Tween,
+        &Position,
+        Option<&Selected>,
+    )>,
+    windows: Query<&Window>,
+) {
+    let window = windows.single();
+    let window_size = Vec2::new(window.width(), window.height());
+
+    tweens
+        .iter_mut()
+        .for_each(|(entity, mut transform, mut tween, position, selected)| {
+            // A piece picked up mid-animation belongs to the cursor instead.
+            if selected.is_some() {
+                commands.entity(entity).remove::<Tween>();
+                return;
+            }
+
+            tween.timer.tick(time.delta());
+            let end = orientation.translation(&board, position, transform.scale.x, window_size);
+            let progress = tween.timer.percent();
+            let eased = 1.0 - (1.0 - progress).powi(3);
+
+            if tween.timer.finished() {
+                transform.translation = end.extend(0.5);
+                commands.entity(entity).remove::<Tween>();
+            } else {
+                transform.translation = tween.start.lerp(end, eased).extend(0.75);
+            }
+        });
+}
+
+fn animate_captures(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut fades: Query<(Entity, &Handle<ColorMaterial>, &mut FadeOut)>,
+) {
+    fades.iter_mut().for_each(|(entity, material, mut fade)| {
+        fade.0.tick(time.delta());
+        if fade.0.finished() {
+            commands.entity(entity).despawn();
+        } else if let Some(material) = materials.get_mut(material) {
+            material.color.set_a(fade.0.percent_left());
+        }
+    });
+}
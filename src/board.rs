@@ -18,7 +18,7 @@
 //! A module for the creation and management of a chessboard.
 
 use bevy::prelude::{Component, Resource};
-use std::{mem, process};
+use std::{fmt, mem};
 
 type BoardLayout = Vec<Vec<Square>>;
 type MoveList = Vec<Move>;
@@ -38,7 +38,7 @@ type MoveList = Vec<Move>;
 ///     y: 1,
 /// };
 /// ```
-#[derive(Clone, Copy, Component, Debug)]
+#[derive(Clone, Copy, Component, Debug, PartialEq, Eq)]
 pub struct Position {
     /// The x-coordinate.
     pub x: usize,
@@ -46,6 +46,21 @@ pub struct Position {
     pub y: usize,
 }
 
+impl fmt::Display for Position {
+    /// Formats the position as a square in algebraic notation, such as `e4`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::board::Position;
+    ///
+    /// assert_eq!(Position { x: 4, y: 4 }.to_string(), "e4");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", (b'a' + self.x as u8) as char, 8 - self.y)
+    }
+}
+
 /// A struct representing a piece movement.
 ///
 /// Contains two fields, the old and new position of a piece, which are of type `Position`.
@@ -60,7 +75,7 @@ pub struct Position {
 ///     new_pos: Position { x: 1, y: 3 },
 /// };
 /// ```
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Move {
     /// The old position of the piece.
     pub old_pos: Position,
@@ -91,6 +106,24 @@ pub enum PieceColour {
     White,
 }
 
+impl PieceColour {
+    /// Returns the colour of the other player.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::board::PieceColour;
+    ///
+    /// assert_eq!(PieceColour::White.opponent(), PieceColour::Black);
+    /// ```
+    pub fn opponent(&self) -> Self {
+        match self {
+            PieceColour::White => PieceColour::Black,
+            PieceColour::Black => PieceColour::White,
+        }
+    }
+}
+
 /// An enum representing the possible kinds of a piece.
 ///
 /// Contains six variants, all with their own unique set of valid moves.
@@ -158,6 +191,7 @@ impl PieceKind {
                         || ((pos.old_pos.y as i8 - pos.new_pos.y as i8) == -2)
                             && is_path_empty(pos, board)
                             && (pos.old_pos.y == 1)
+                            && (board.layout[pos.new_pos.y][pos.new_pos.x].kind().is_none())
                             && (pos.old_pos.x == pos.new_pos.x)
                 }
                 PieceColour::White => {
@@ -172,6 +206,7 @@ impl PieceKind {
                         || ((pos.old_pos.y as i8 - pos.new_pos.y as i8) == 2)
                             && is_path_empty(pos, board)
                             && (pos.old_pos.y == 6)
+                            && (board.layout[pos.new_pos.y][pos.new_pos.x].kind().is_none())
                             && (pos.old_pos.x == pos.new_pos.x)
                 }
             },
@@ -195,6 +230,28 @@ impl PieceKind {
         }
     }
 
+    /// Returns the letter used for the piece in algebraic notation.
+    ///
+    /// Pawns have no letter, so an empty string is returned for them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::board::PieceKind;
+    ///
+    /// assert_eq!(PieceKind::Knight.letter(), "N");
+    /// ```
+    pub fn letter(&self) -> &'static str {
+        match self {
+            PieceKind::Bishop => "B",
+            PieceKind::King => "K",
+            PieceKind::Knight => "N",
+            PieceKind::Pawn => "",
+            PieceKind::Queen => "Q",
+            PieceKind::Rook => "R",
+        }
+    }
+
     /// Returns the value of the piece.
     ///
     /// # Examples
@@ -393,9 +450,47 @@ impl Square {
     }
 }
 
+/// An enum representing the way a game has ended.
+///
+/// # Examples
+///
+/// ```
+/// use chess::board::{Outcome, PieceColour};
+///
+/// let outcome = Outcome::Checkmate(PieceColour::White);
+/// assert_eq!(outcome.to_string(), "White wins by checkmate");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// A player was checkmated.
+    ///
+    /// Contains the colour of the winner.
+    Checkmate(PieceColour),
+    /// The player to move had no valid moves, but was not in check.
+    Stalemate,
+    /// A player ran out of time.
+    ///
+    /// Contains the colour of the winner.
+    Timeout(PieceColour),
+    /// A player ran out of time, but their opponent could not have checkmated them.
+    TimeoutDraw,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Checkmate(winner) => write!(f, "{:?} wins by checkmate", winner),
+            Outcome::Stalemate => write!(f, "Draw by stalemate"),
+            Outcome::Timeout(winner) => write!(f, "{:?} wins on time", winner),
+            Outcome::TimeoutDraw => write!(f, "Draw by timeout against insufficient material"),
+        }
+    }
+}
+
 /// A struct representing a chessboard.
 ///
-/// Contains three fields, a layout, the current player, and the move list.
+/// Contains a layout, the current player, and the move list, along with the
+/// history needed to undo and redo moves.
 ///
 /// # Examples
 ///
@@ -404,20 +499,18 @@ impl Square {
 ///
 /// let board = Board::default();
 /// ```
-#[derive(Resource)]
+#[derive(Clone, Resource)]
 pub struct Board {
     layout: BoardLayout,
     move_list: MoveList,
     player: PieceColour,
+    history: Vec<(BoardLayout, String)>,
+    undone: Vec<(Move, String)>,
 }
 
 impl Default for Board {
     fn default() -> Self {
-        Self {
-            layout: Layouts::standard(),
-            move_list: vec![],
-            player: PieceColour::White,
-        }
+        Self::new(Layouts::standard(), vec![], PieceColour::White)
     }
 }
 
@@ -477,8 +570,6 @@ impl Board {
             }
         }
 
-        // TODO: Valid move checks
-        // piece_kind.valid_moves();
         if !old_square
             .kind()
             .unwrap()
@@ -487,9 +578,128 @@ impl Board {
             return Err("Error: Move is invalid!");
         };
 
+        // Player trying to leave their own king in check
+        if Board::new(self.layout_after(piece_move), vec![], self.player).is_check(&self.player) {
+            return Err("Error: You cannot leave your king in check!");
+        }
+
         Ok(())
     }
 
+    /// Returns the valid moves for the piece at the given position.
+    ///
+    /// Every destination square is checked with `check_valid`, so the returned
+    /// moves are exactly the ones `move_piece` would accept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::board::{Board, Position};
+    ///
+    /// let board = Board::default();
+    /// let moves = board.valid_moves(&Position { x: 1, y: 7 });
+    /// assert_eq!(moves.len(), 2);
+    /// ```
+    pub fn valid_moves(&self, position: &Position) -> MoveList {
+        self.layout
+            .iter()
+            .enumerate()
+            .flat_map(|(y, rank)| (0..rank.len()).map(move |x| Position { x, y }))
+            .map(|new_pos| Move {
+                old_pos: *position,
+                new_pos,
+            })
+            .filter(|piece_move| self.check_valid(piece_move).is_ok())
+            .collect()
+    }
+
+    /// Returns the position of the king of the given colour, if it is on the board.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::board::{Board, PieceColour};
+    ///
+    /// let board = Board::default();
+    /// println!("{:?}", board.king_position(&PieceColour::White));
+    /// ```
+    pub fn king_position(&self, colour: &PieceColour) -> Option<Position> {
+        self.layout.iter().enumerate().find_map(|(y, rank)| {
+            rank.iter()
+                .position(|square| {
+                    square
+                        == &Square::Piece {
+                            piece_colour: *colour,
+                            piece_kind: PieceKind::King,
+                        }
+                })
+                .map(|x| Position { x, y })
+        })
+    }
+
+    /// Checks if the king of the given colour is in check.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::board::{Board, PieceColour};
+    ///
+    /// let board = Board::default();
+    /// assert!(!board.is_check(&PieceColour::White));
+    /// ```
+    pub fn is_check(&self, colour: &PieceColour) -> bool {
+        let king_pos = match self.king_position(colour) {
+            Some(position) => position,
+            None => return false,
+        };
+
+        self.layout.iter().enumerate().any(|(y, rank)| {
+            rank.iter().enumerate().any(|(x, square)| match square {
+                Square::Piece {
+                    piece_colour,
+                    piece_kind,
+                } if piece_colour != colour => piece_kind.is_move_valid(
+                    &Move {
+                        old_pos: Position { x, y },
+                        new_pos: king_pos,
+                    },
+                    piece_colour,
+                    self,
+                ),
+                _ => false,
+            })
+        })
+    }
+
+    /// Returns how the game has ended, if it has ended.
+    ///
+    /// The game ends once the player to move has no valid moves left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::board::Board;
+    ///
+    /// let board = Board::default();
+    /// assert_eq!(board.outcome(), None);
+    /// ```
+    pub fn outcome(&self) -> Option<Outcome> {
+        let has_valid_moves = self.layout.iter().enumerate().any(|(y, rank)| {
+            rank.iter().enumerate().any(|(x, square)| {
+                square.colour() == Some(&self.player)
+                    && !self.valid_moves(&Position { x, y }).is_empty()
+            })
+        });
+
+        if has_valid_moves {
+            None
+        } else if self.is_check(&self.player) {
+            Some(Outcome::Checkmate(self.player.opponent()))
+        } else {
+            Some(Outcome::Stalemate)
+        }
+    }
+
     /// Returns a reference to the board layout.
     ///
     /// # Examples
@@ -535,31 +745,375 @@ impl Board {
     /// }).unwrap_or_else(|err| eprintln!("{}", err));
     /// ```
     pub fn move_piece(&mut self, piece_move: Move) -> Result<(), &str> {
-        if let Err(err) = self.check_valid(&piece_move) {
-            return Err(err);
-        }
-        let moved_piece = mem::replace(
-            &mut self.layout[piece_move.old_pos.y][piece_move.old_pos.x],
-            Square::Empty,
-        );
-        if let Some(king) = self.layout[piece_move.new_pos.y][piece_move.new_pos.x].kind() {
-            if king == &PieceKind::King {
-                match moved_piece.colour().unwrap() {
-                    PieceColour::Black => {
-                        println!("Black wins!");
-                        process::exit(0);
-                    }
-                    PieceColour::White => {
-                        println!("White wins!");
-                        process::exit(0);
+        self.check_valid(&piece_move)?;
+        let san = self.to_san(&piece_move);
+        self.undone.clear();
+        self.make_move(piece_move, san);
+        Ok(())
+    }
+
+    /// Takes back the last move.
+    ///
+    /// Returns an error if there are no moves to take back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::board::{Board, Move, Position};
+    ///
+    /// let mut board = Board::default();
+    /// board.move_piece(Move {
+    ///     old_pos: Position { x: 4, y: 6 },
+    ///     new_pos: Position { x: 4, y: 4 },
+    /// }).unwrap();
+    /// board.undo().unwrap();
+    /// assert!(board.move_list().is_empty());
+    /// ```
+    pub fn undo(&mut self) -> Result<(), &str> {
+        let (layout, san) = match self.history.pop() {
+            Some(entry) => entry,
+            None => return Err("Error: There are no moves to undo!"),
+        };
+        let piece_move = self.move_list.pop().unwrap();
+        self.layout = layout;
+        self.undone.push((piece_move, san));
+        self.next_turn();
+        Ok(())
+    }
+
+    /// Replays the last move that was taken back.
+    ///
+    /// Returns an error if there are no moves to replay.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::board::{Board, Move, Position};
+    ///
+    /// let mut board = Board::default();
+    /// board.move_piece(Move {
+    ///     old_pos: Position { x: 4, y: 6 },
+    ///     new_pos: Position { x: 4, y: 4 },
+    /// }).unwrap();
+    /// board.undo().unwrap();
+    /// board.redo().unwrap();
+    /// assert_eq!(board.move_list().len(), 1);
+    /// ```
+    pub fn redo(&mut self) -> Result<(), &str> {
+        let (piece_move, san) = match self.undone.pop() {
+            Some(entry) => entry,
+            None => return Err("Error: There are no moves to redo!"),
+        };
+        self.make_move(piece_move, san);
+        Ok(())
+    }
+
+    /// Returns the number of moves that can be taken back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::board::Board;
+    ///
+    /// let board = Board::default();
+    /// assert_eq!(board.ply(), 0);
+    /// ```
+    pub fn ply(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Returns the pieces of the given colour that have been captured, from the
+    /// least to the most valuable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::board::{Board, PieceColour};
+    ///
+    /// let board = Board::default();
+    /// assert!(board.captured_pieces(&PieceColour::Black).is_empty());
+    /// ```
+    pub fn captured_pieces(&self, colour: &PieceColour) -> Vec<PieceKind> {
+        let offset = self.move_list.len() - self.history.len();
+        let mut captured: Vec<PieceKind> = self
+            .history
+            .iter()
+            .zip(&self.move_list[offset..])
+            .filter_map(|((layout, _), piece_move)| {
+                match layout[piece_move.new_pos.y][piece_move.new_pos.x] {
+                    Square::Piece {
+                        piece_colour,
+                        piece_kind,
+                    } if &piece_colour == colour => Some(piece_kind),
+                    _ => None,
+                }
+            })
+            .collect();
+        captured.sort_by_key(|piece_kind| piece_kind.value());
+        captured
+    }
+
+    /// Returns the total value of the pieces of the given colour on the board.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::board::{Board, PieceColour};
+    ///
+    /// let board = Board::default();
+    /// assert_eq!(board.material(&PieceColour::White), 39);
+    /// ```
+    pub fn material(&self, colour: &PieceColour) -> u32 {
+        self.layout
+            .iter()
+            .flatten()
+            .filter_map(|square| match square {
+                Square::Piece {
+                    piece_colour,
+                    piece_kind,
+                } if piece_colour == colour => Some(piece_kind.value() as u32),
+                _ => None,
+            })
+            .sum()
+    }
+
+    /// Returns whether the given colour has enough material to checkmate.
+    ///
+    /// A lone king can never checkmate, and neither can a king with a single
+    /// bishop or knight against a lone king.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::board::{Board, PieceColour};
+    ///
+    /// let board = Board::from_fen("4k3/8/8/8/8/8/8/2B1K3 w - - 0 1").unwrap();
+    /// assert!(!board.can_checkmate(&PieceColour::White));
+    /// assert!(!board.can_checkmate(&PieceColour::Black));
+    /// ```
+    pub fn can_checkmate(&self, colour: &PieceColour) -> bool {
+        let pieces = |colour: &PieceColour| -> Vec<PieceKind> {
+            self.layout
+                .iter()
+                .flatten()
+                .filter_map(|square| match square {
+                    Square::Piece {
+                        piece_colour,
+                        piece_kind,
+                    } if piece_colour == colour && *piece_kind != PieceKind::King => {
+                        Some(*piece_kind)
                     }
+                    _ => None,
+                })
+                .collect()
+        };
+        let opponent = colour.opponent();
+
+        match pieces(colour)[..] {
+            [] => false,
+            [PieceKind::Bishop] | [PieceKind::Knight] => !pieces(&opponent).is_empty(),
+            _ => true,
+        }
+    }
+
+    /// Returns the moves of the game in standard algebraic notation.
+    ///
+    /// The moves that have been played come first, followed by any moves that
+    /// have been taken back and can still be replayed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::board::{Board, Move, Position};
+    ///
+    /// let mut board = Board::default();
+    /// board.move_piece(Move {
+    ///     old_pos: Position { x: 6, y: 7 },
+    ///     new_pos: Position { x: 5, y: 5 },
+    /// }).unwrap();
+    /// assert_eq!(board.san_list(), vec!["Nf3"]);
+    /// ```
+    pub fn san_list(&self) -> Vec<String> {
+        self.history
+            .iter()
+            .map(|(_, san)| san.clone())
+            .chain(self.undone.iter().rev().map(|(_, san)| san.clone()))
+            .collect()
+    }
+
+    /// Returns a move in standard algebraic notation.
+    ///
+    /// The move is described as it would be played from the current position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::board::{Board, Move, Position};
+    ///
+    /// let board = Board::default();
+    /// let san = board.to_san(&Move {
+    ///     old_pos: Position { x: 4, y: 6 },
+    ///     new_pos: Position { x: 4, y: 4 },
+    /// });
+    /// assert_eq!(san, "e4");
+    /// ```
+    pub fn to_san(&self, piece_move: &Move) -> String {
+        let old_square = self.layout[piece_move.old_pos.y][piece_move.old_pos.x];
+        let (piece_colour, piece_kind) = match old_square {
+            Square::Piece {
+                piece_colour,
+                piece_kind,
+            } => (piece_colour, piece_kind),
+            Square::Empty => return String::new(),
+        };
+        let is_capture = self.layout[piece_move.new_pos.y][piece_move.new_pos.x] != Square::Empty;
+        let origin = piece_move.old_pos.to_string();
+
+        let mut san = String::from(piece_kind.letter());
+        if piece_kind == PieceKind::Pawn {
+            if is_capture {
+                san.push_str(&origin[..1]);
+            }
+        } else {
+            // Other pieces of the same kind that could also reach the destination.
+            let rivals: Vec<Position> = self
+                .layout
+                .iter()
+                .enumerate()
+                .flat_map(|(y, rank)| {
+                    rank.iter()
+                        .enumerate()
+                        .filter(move |(_, square)| **square == old_square)
+                        .map(move |(x, _)| Position { x, y })
+                })
+                .filter(|position| *position != piece_move.old_pos)
+                .filter(|position| {
+                    self.check_valid(&Move {
+                        old_pos: *position,
+                        new_pos: piece_move.new_pos,
+                    })
+                    .is_ok()
+                })
+                .collect();
+
+            if !rivals.is_empty() {
+                if rivals.iter().all(|rival| rival.x != piece_move.old_pos.x) {
+                    san.push_str(&origin[..1]);
+                } else if rivals.iter().all(|rival| rival.y != piece_move.old_pos.y) {
+                    san.push_str(&origin[1..]);
+                } else {
+                    san.push_str(&origin);
                 }
             }
         }
-        self.layout[piece_move.new_pos.y][piece_move.new_pos.x] = moved_piece;
-        self.move_list.push(piece_move);
-        self.next_turn();
-        Ok(())
+        if is_capture {
+            san.push('x');
+        }
+        san.push_str(&piece_move.new_pos.to_string());
+
+        let opponent = piece_colour.opponent();
+        let next_board = Board::new(self.layout_after(piece_move), vec![], opponent);
+        if next_board.is_check(&opponent) {
+            san.push(if next_board.outcome().is_some() {
+                '#'
+            } else {
+                '+'
+            });
+        }
+
+        san
+    }
+
+    /// Creates a new chessboard from a position in Forsyth-Edwards Notation.
+    ///
+    /// Only the piece placement and the active colour are read, as the board does
+    /// not track castling rights, en passant or the move clocks. Positions
+    /// without exactly one king of each colour, or where the side not to move
+    /// is already in check, are rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::board::{Board, PieceColour};
+    ///
+    /// let board = Board::from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 1").unwrap();
+    /// assert_eq!(board.player(), &PieceColour::Black);
+    /// ```
+    pub fn from_fen(fen: &str) -> Result<Self, &'static str> {
+        let mut fields = fen.split_whitespace();
+        let (placement, active_colour) = match (fields.next(), fields.next()) {
+            (Some(placement), Some(active_colour)) => (placement, active_colour),
+            _ => return Err("Error: FEN must contain a piece placement and an active colour!"),
+        };
+
+        let mut layout: BoardLayout = vec![];
+        for rank in placement.split('/') {
+            let mut squares = vec![];
+            for c in rank.chars() {
+                if let Some(empty) = c.to_digit(10) {
+                    squares.extend((0..empty).map(|_| Square::Empty));
+                    continue;
+                }
+                let piece_kind = match c.to_ascii_lowercase() {
+                    'b' => PieceKind::Bishop,
+                    'k' => PieceKind::King,
+                    'n' => PieceKind::Knight,
+                    'p' => PieceKind::Pawn,
+                    'q' => PieceKind::Queen,
+                    'r' => PieceKind::Rook,
+                    _ => return Err("Error: FEN contains an invalid piece!"),
+                };
+                let piece_colour = if c.is_ascii_uppercase() {
+                    PieceColour::White
+                } else {
+                    PieceColour::Black
+                };
+                squares.push(Square::Piece {
+                    piece_colour,
+                    piece_kind,
+                });
+            }
+            if squares.len() != 8 {
+                return Err("Error: FEN ranks must contain 8 squares!");
+            }
+            layout.push(squares);
+        }
+        if layout.len() != 8 {
+            return Err("Error: FEN must contain 8 ranks!");
+        }
+
+        let player = match active_colour {
+            "w" => PieceColour::White,
+            "b" => PieceColour::Black,
+            _ => return Err("Error: FEN contains an invalid active colour!"),
+        };
+
+        // A side without a king could never be checkmated.
+        let has_one_king = |piece_colour| {
+            layout
+                .iter()
+                .flatten()
+                .filter(|square| {
+                    **square
+                        == Square::Piece {
+                            piece_colour,
+                            piece_kind: PieceKind::King,
+                        }
+                })
+                .count()
+                == 1
+        };
+        if !has_one_king(PieceColour::White) || !has_one_king(PieceColour::Black) {
+            return Err("Error: FEN must contain exactly one king of each colour!");
+        }
+
+        // The side to move could otherwise capture the opposing king.
+        let board = Self::new(layout, vec![], player);
+        if board.is_check(&player.opponent()) {
+            return Err("Error: FEN must not leave the side not to move in check!");
+        }
+
+        Ok(board)
     }
 
     /// Creates a new chessboard with the given configuration.
@@ -568,15 +1122,46 @@ impl Board {
             layout,
             move_list,
             player,
+            history: vec![],
+            undone: vec![],
         }
     }
 
+    /// Plays a move that is known to be valid, without building its SAN.
+    ///
+    /// Searches play far more moves than are ever shown, and `to_san` works out
+    /// both the disambiguation and the outcome of every move it is given.
+    pub(crate) fn make_valid_move(&mut self, piece_move: Move) {
+        self.undone.clear();
+        self.make_move(piece_move, String::new());
+    }
+
+    /// Plays a move that is known to be valid, recording it in the history.
+    fn make_move(&mut self, piece_move: Move, san: String) {
+        let previous_layout = self.layout.clone();
+        let moved_piece = mem::replace(
+            &mut self.layout[piece_move.old_pos.y][piece_move.old_pos.x],
+            Square::Empty,
+        );
+        self.layout[piece_move.new_pos.y][piece_move.new_pos.x] = moved_piece;
+        self.history.push((previous_layout, san));
+        self.move_list.push(piece_move);
+        self.next_turn();
+    }
+
+    /// Returns the layout of the board after a move, without checking that it is valid.
+    fn layout_after(&self, piece_move: &Move) -> BoardLayout {
+        let mut layout = self.layout.clone();
+        layout[piece_move.new_pos.y][piece_move.new_pos.x] = mem::replace(
+            &mut layout[piece_move.old_pos.y][piece_move.old_pos.x],
+            Square::Empty,
+        );
+        layout
+    }
+
     /// Switches to the next player.
     fn next_turn(&mut self) {
-        self.player = match self.player {
-            PieceColour::White => PieceColour::Black,
-            PieceColour::Black => PieceColour::White,
-        }
+        self.player = self.player.opponent();
     }
 
     /// Returns a reference to the current player.
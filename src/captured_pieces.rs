@@ -0,0 +1,127 @@
+// Copyright (C) 2023  Brynley Llewellyn-Roux and Aryan Jassal
+//
+// This file is part of chess.
+//
+// chess is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published
+// by the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// chess is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A module for the captured pieces plugin.
+
+use crate::{
+    board::*,
+    orientation::Orientation,
+    pieces::texture_path,
+    theme::Theme,
+    update_dimensions::{BesideBoard, SIDE_PANEL_WIDTH},
+};
+use bevy::prelude::*;
+
+const PIECE_SIZE: f32 = 24.0;
+
+/// A plugin implementing trays of captured pieces and a material balance display.
+pub struct CapturedPiecesPlugin;
+
+impl Plugin for CapturedPiecesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_trays)
+            .add_systems(Update, update_trays);
+    }
+}
+
+/// A tray holding the pieces captured by a player.
+#[derive(Component)]
+struct CaptureTray(PieceColour);
+
+fn setup_trays(mut commands: Commands) {
+    [PieceColour::White, PieceColour::Black]
+        .into_iter()
+        .for_each(|colour| {
+            commands.spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        width: Val::Px(SIDE_PANEL_WIDTH),
+                        flex_wrap: FlexWrap::Wrap,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                },
+                CaptureTray(colour),
+                BesideBoard,
+            ));
+        });
+}
+
+fn update_trays(
+    board: Res<Board>,
+    orientation: Res<Orientation>,
+    theme: Res<Theme>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut trays: Query<(Entity, &mut Style, &CaptureTray)>,
+) {
+    if !board.is_changed() && !orientation.is_changed() && !theme.is_changed() {
+        return;
+    }
+
+    trays.iter_mut().for_each(|(entity, mut style, tray)| {
+        let opponent = tray.0.opponent();
+
+        // Each player's tray sits on their own side of the board.
+        (style.top, style.bottom) = if tray.0 == orientation.bottom {
+            (Val::Auto, Val::Px(15.0))
+        } else {
+            (Val::Px(15.0), Val::Auto)
+        };
+
+        let advantage = board.material(&tray.0) as i32 - board.material(&opponent) as i32;
+
+        commands.entity(entity).despawn_descendants();
+        commands.entity(entity).with_children(|parent| {
+            board
+                .captured_pieces(&opponent)
+                .iter()
+                .for_each(|piece_kind| {
+                    parent.spawn(ImageBundle {
+                        style: Style {
+                            width: Val::Px(PIECE_SIZE),
+                            height: Val::Px(PIECE_SIZE),
+                            ..default()
+                        },
+                        image: asset_server
+                            .load(texture_path(&theme.piece_set, &opponent, piece_kind))
+                            .into(),
+                        ..default()
+                    });
+                });
+
+            if advantage > 0 {
+                parent.spawn(
+                    TextBundle::from_section(
+                        format!("+{advantage}"),
+                        TextStyle {
+                            font: asset_server.load("fonts/text.ttf"),
+                            font_size: 16.0,
+                            color: Color::WHITE,
+                        },
+                    )
+                    .with_style(Style {
+                        margin: UiRect::left(Val::Px(5.0)),
+                        ..default()
+                    }),
+                );
+            }
+        });
+    });
+}
@@ -0,0 +1,554 @@
+// Copyright (C) 2023  Brynley Llewellyn-Roux and Aryan Jassal
+//
+// This file is part of chess.
+//
+// chess is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published
+// by the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// chess is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A module for the chess clock plugin.
+
+use crate::{
+    board::*, drag_and_drop::drag_and_drop, game_state::GameState, orientation::Orientation,
+    update_dimensions::BesideBoard,
+};
+use bevy::prelude::*;
+use std::{fmt, time::Duration};
+
+const ACTIVE_COLOUR: Color = Color::WHITE;
+const INACTIVE_COLOUR: Color = Color::rgb(0.5, 0.5, 0.5);
+const FLAGGED_COLOUR: Color = Color::rgb(0.9, 0.3, 0.3);
+
+/// A plugin implementing chess clocks.
+pub struct ClockPlugin;
+
+impl Plugin for ClockPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Clock>()
+            .add_systems(Startup, setup_clocks)
+            .add_systems(
+                Update,
+                (press_clock, tick_clock)
+                    .chain()
+                    .after(drag_and_drop)
+                    .run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(Update, update_clocks);
+    }
+}
+
+/// A stage of a multi-period time control.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Period {
+    /// The number of moves to be made in this period, or `None` for the rest of the game.
+    pub moves: Option<u32>,
+    /// The time added to the clock when this period starts.
+    pub time: Duration,
+}
+
+/// An enum representing the ways time can be controlled in a game.
+///
+/// # Examples
+///
+/// ```
+/// use chess::clock::TimeControl;
+/// use std::time::Duration;
+///
+/// let time_control = TimeControl::Fischer {
+///     base: Duration::from_secs(180),
+///     increment: Duration::from_secs(2),
+/// };
+/// assert_eq!(time_control.to_string(), "3+2");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum TimeControl {
+    /// No clocks are used.
+    #[default]
+    Untimed,
+    /// A fixed amount of time is added after every move.
+    Fischer {
+        /// The starting time of each player.
+        base: Duration,
+        /// The time added after every move.
+        increment: Duration,
+    },
+    /// After every move, the time spent on it is given back, up to the delay.
+    Bronstein {
+        /// The starting time of each player.
+        base: Duration,
+        /// The most time given back after a move.
+        delay: Duration,
+    },
+    /// The clock only starts counting down once the delay of each move has passed.
+    UsDelay {
+        /// The starting time of each player.
+        base: Duration,
+        /// The time at the start of every move that is not counted.
+        delay: Duration,
+    },
+    /// The time spent by one player is added to the clock of the other.
+    Hourglass {
+        /// The starting time of each player.
+        base: Duration,
+    },
+    /// Time is added as each period of moves is completed.
+    MultiPeriod {
+        /// The periods in the order they are played.
+        periods: Vec<Period>,
+        /// The time added after every move.
+        increment: Duration,
+    },
+}
+
+impl TimeControl {
+    /// Returns the time controls offered when setting up a game.
+    pub fn presets() -> Vec<Self> {
+        vec![
+            TimeControl::Untimed,
+            TimeControl::Fischer {
+                base: Duration::from_secs(3 * 60),
+                increment: Duration::from_secs(2),
+            },
+            TimeControl::Fischer {
+                base: Duration::from_secs(15 * 60),
+                increment: Duration::from_secs(10),
+            },
+            TimeControl::Bronstein {
+                base: Duration::from_secs(5 * 60),
+                delay: Duration::from_secs(3),
+            },
+            TimeControl::UsDelay {
+                base: Duration::from_secs(5 * 60),
+                delay: Duration::from_secs(5),
+            },
+            TimeControl::Hourglass {
+                base: Duration::from_secs(60),
+            },
+            TimeControl::MultiPeriod {
+                periods: vec![
+                    Period {
+                        moves: Some(40),
+                        time: Duration::from_secs(90 * 60),
+                    },
+                    Period {
+                        moves: None,
+                        time: Duration::from_secs(30 * 60),
+                    },
+                ],
+                increment: Duration::from_secs(30),
+            },
+        ]
+    }
+
+    /// Returns the preset after this one, or the first preset for a time control that is not one.
+    pub fn next(&self) -> Self {
+        let presets = TimeControl::presets();
+        let index = presets.iter().position(|preset| preset == self);
+        presets[index.map_or(0, |index| (index + 1) % presets.len())].clone()
+    }
+
+    fn base(&self) -> Duration {
+        match self {
+            TimeControl::Untimed => Duration::ZERO,
+            TimeControl::Fischer { base, .. }
+            | TimeControl::Bronstein { base, .. }
+            | TimeControl::UsDelay { base, .. }
+            | TimeControl::Hourglass { base } => *base,
+            TimeControl::MultiPeriod { periods, .. } => {
+                periods.first().map_or(Duration::ZERO, |period| period.time)
+            }
+        }
+    }
+}
+
+impl fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let minutes = |duration: &Duration| duration.as_secs() / 60;
+
+        match self {
+            TimeControl::Untimed => write!(f, "Untimed"),
+            TimeControl::Fischer { base, increment } => {
+                write!(f, "{}+{}", minutes(base), increment.as_secs())
+            }
+            TimeControl::Bronstein { base, delay } => write!(
+                f,
+                "{} min, {}s Bronstein delay",
+                minutes(base),
+                delay.as_secs()
+            ),
+            TimeControl::UsDelay { base, delay } => {
+                write!(f, "{} min, {}s delay", minutes(base), delay.as_secs())
+            }
+            TimeControl::Hourglass { base } => write!(f, "{} min hourglass", minutes(base)),
+            TimeControl::MultiPeriod { periods, increment } => {
+                let periods: Vec<_> = periods
+                    .iter()
+                    .map(|period| match period.moves {
+                        Some(moves) => format!("{}/{}", moves, minutes(&period.time)),
+                        None => minutes(&period.time).to_string(),
+                    })
+                    .collect();
+                write!(f, "{}", periods.join(" + "))?;
+                if !increment.is_zero() {
+                    write!(f, ", {}s increment", increment.as_secs())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A resource representing the clocks of both players.
+///
+/// The clock of the player to move is ticked down by the game, and pressed
+/// once they have made their move. The clocks are recorded at every ply, so
+/// that undoing and redoing moves puts them back as they were.
+///
+/// # Examples
+///
+/// ```
+/// use chess::{board::PieceColour, clock::{Clock, TimeControl}};
+/// use std::time::Duration;
+///
+/// let mut clock = Clock::new(TimeControl::Fischer {
+///     base: Duration::from_secs(60),
+///     increment: Duration::from_secs(2),
+/// });
+/// clock.tick(&PieceColour::White, Duration::from_secs(5));
+/// clock.press(&PieceColour::White, 1);
+/// assert_eq!(clock.remaining(&PieceColour::White), Some(Duration::from_secs(57)));
+/// ```
+#[derive(Clone, Debug, Resource)]
+pub struct Clock {
+    time_control: TimeControl,
+    remaining: [Duration; 2],
+    period: [usize; 2],
+    thinking: Duration,
+    flagged: Option<PieceColour>,
+    ply: usize,
+    history: Vec<Snapshot>,
+}
+
+/// The clocks as they were once a ply was reached.
+#[derive(Clone, Debug)]
+struct Snapshot {
+    piece_move: Option<Move>,
+    remaining: [Duration; 2],
+    period: [usize; 2],
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new(TimeControl::Untimed)
+    }
+}
+
+impl Clock {
+    /// Creates a new clock with the given time control.
+    pub fn new(time_control: TimeControl) -> Self {
+        let remaining = [time_control.base(); 2];
+        Self {
+            time_control,
+            remaining,
+            period: [0; 2],
+            thinking: Duration::ZERO,
+            flagged: None,
+            ply: 0,
+            history: vec![Snapshot {
+                piece_move: None,
+                remaining,
+                period: [0; 2],
+            }],
+        }
+    }
+
+    /// Returns the time control of the clock.
+    pub fn time_control(&self) -> &TimeControl {
+        &self.time_control
+    }
+
+    /// Returns the time left for the given colour, or `None` if the game is untimed.
+    pub fn remaining(&self, colour: &PieceColour) -> Option<Duration> {
+        match self.time_control {
+            TimeControl::Untimed => None,
+            _ => Some(self.remaining[index(colour)]),
+        }
+    }
+
+    /// Returns the colour that ran out of time, if either has.
+    pub fn flagged(&self) -> Option<&PieceColour> {
+        self.flagged.as_ref()
+    }
+
+    /// Counts down the clock of the player to move.
+    pub fn tick(&mut self, player: &PieceColour, delta: Duration) {
+        if self.time_control == TimeControl::Untimed || self.flagged.is_some() {
+            return;
+        }
+
+        let before = self.thinking;
+        self.thinking += delta;
+        let charged = match self.time_control {
+            TimeControl::UsDelay { delay, .. } => {
+                self.thinking.saturating_sub(delay) - before.saturating_sub(delay)
+            }
+            _ => delta,
+        };
+
+        let player = index(player);
+        let charged = charged.min(self.remaining[player]);
+        if let TimeControl::Hourglass { .. } = self.time_control {
+            self.remaining[1 - player] += charged;
+        }
+        self.remaining[player] -= charged;
+
+        if self.remaining[player].is_zero() {
+            self.flagged = Some(colour(player));
+        }
+    }
+
+    /// Follows the board after it has changed.
+    ///
+    /// Each new move presses the clock of the player who made it. Undoing or
+    /// redoing moves instead restores the clocks recorded at that ply, so no
+    /// increment is ever added twice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::{
+    ///     board::{Board, Move, PieceColour, Position},
+    ///     clock::{Clock, TimeControl},
+    /// };
+    /// use std::time::Duration;
+    ///
+    /// let mut board = Board::default();
+    /// let mut clock = Clock::new(TimeControl::Fischer {
+    ///     base: Duration::from_secs(60),
+    ///     increment: Duration::from_secs(2),
+    /// });
+    /// board.move_piece(Move {
+    ///     old_pos: Position { x: 4, y: 6 },
+    ///     new_pos: Position { x: 4, y: 4 },
+    /// }).unwrap();
+    /// clock.update(&board);
+    /// assert_eq!(clock.remaining(&PieceColour::White), Some(Duration::from_secs(62)));
+    ///
+    /// board.undo().unwrap();
+    /// clock.update(&board);
+    /// assert_eq!(clock.remaining(&PieceColour::White), Some(Duration::from_secs(60)));
+    /// ```
+    pub fn update(&mut self, board: &Board) {
+        let ply = board.ply();
+        let move_list = board.move_list();
+        let moves = &move_list[move_list.len() - ply..];
+
+        // The number of the board's moves that the clock has already recorded.
+        let known = self.history[1..]
+            .iter()
+            .zip(moves)
+            .take_while(|(snapshot, piece_move)| snapshot.piece_move == Some(**piece_move))
+            .count();
+
+        if ply <= known {
+            if ply != self.ply {
+                self.restore(ply);
+            }
+            return;
+        }
+
+        if known != self.ply {
+            self.restore(known);
+        }
+        self.history.truncate(known + 1);
+
+        moves[known..]
+            .iter()
+            .enumerate()
+            .for_each(|(offset, piece_move)| {
+                let move_ply = known + offset + 1;
+                let mover = if (ply - move_ply).is_multiple_of(2) {
+                    board.player().opponent()
+                } else {
+                    *board.player()
+                };
+                self.press(&mover, move_ply);
+                self.history.push(Snapshot {
+                    piece_move: Some(*piece_move),
+                    remaining: self.remaining,
+                    period: self.period,
+                });
+            });
+        self.ply = ply;
+    }
+
+    /// Stops the clock of a player after they have made the move at the given ply.
+    ///
+    /// The ply is the number of moves made in the game, including this one.
+    pub fn press(&mut self, player: &PieceColour, ply: usize) {
+        if self.time_control == TimeControl::Untimed || self.flagged.is_some() {
+            return;
+        }
+
+        let player = index(player);
+        // The player to move alternates, so this is their move count whoever started.
+        let player_moves = (ply as u32).div_ceil(2);
+
+        match &self.time_control {
+            TimeControl::Fischer { increment, .. } => self.remaining[player] += *increment,
+            TimeControl::Bronstein { delay, .. } => {
+                self.remaining[player] += self.thinking.min(*delay)
+            }
+            TimeControl::MultiPeriod { periods, increment } => {
+                // Completing the moves of a period adds the time of the next one.
+                let moves: u32 = periods[..=self.period[player]]
+                    .iter()
+                    .filter_map(|period| period.moves)
+                    .sum();
+                if periods[self.period[player]].moves.is_some()
+                    && player_moves == moves
+                    && self.period[player] + 1 < periods.len()
+                {
+                    self.period[player] += 1;
+                    self.remaining[player] += periods[self.period[player]].time;
+                }
+                self.remaining[player] += *increment;
+            }
+            _ => (),
+        }
+
+        self.thinking = Duration::ZERO;
+    }
+
+    /// Puts the clocks back to how they were once the given ply was reached.
+    fn restore(&mut self, ply: usize) {
+        let snapshot = &self.history[ply];
+        self.remaining = snapshot.remaining;
+        self.period = snapshot.period;
+        self.thinking = Duration::ZERO;
+        self.flagged = None;
+        self.ply = ply;
+    }
+
+    /// Returns the outcome of the game if a player has run out of time.
+    ///
+    /// Running out of time loses the game, unless the opponent could never
+    /// have checkmated them, in which case the game is drawn.
+    pub fn outcome(&self, board: &Board) -> Option<Outcome> {
+        self.flagged.map(|loser| {
+            let winner = colour(1 - index(&loser));
+            if board.can_checkmate(&winner) {
+                Outcome::Timeout(winner)
+            } else {
+                Outcome::TimeoutDraw
+            }
+        })
+    }
+}
+
+fn index(colour: &PieceColour) -> usize {
+    match colour {
+        PieceColour::White => 0,
+        PieceColour::Black => 1,
+    }
+}
+
+fn colour(index: usize) -> PieceColour {
+    match index {
+        0 => PieceColour::White,
+        _ => PieceColour::Black,
+    }
+}
+
+/// Formats the time left on a clock, showing tenths of a second when it is low.
+fn format_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60
+        )
+    } else if seconds >= 10 {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    } else {
+        format!("0:{:02}.{}", seconds, time.subsec_millis() / 100)
+    }
+}
+
+/// The clock display of a player.
+#[derive(Component)]
+struct ClockText(PieceColour);
+
+fn setup_clocks(mut commands: Commands, asset_server: Res<AssetServer>) {
+    [PieceColour::White, PieceColour::Black]
+        .into_iter()
+        .for_each(|colour| {
+            commands.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("fonts/text.ttf"),
+                        font_size: 32.0,
+                        color: INACTIVE_COLOUR,
+                    },
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    ..default()
+                }),
+                ClockText(colour),
+                BesideBoard,
+            ));
+        });
+}
+
+fn press_clock(board: Res<Board>, mut clock: ResMut<Clock>) {
+    if board.is_changed() {
+        clock.update(&board);
+    }
+}
+
+fn tick_clock(time: Res<Time>, board: Res<Board>, mut clock: ResMut<Clock>) {
+    clock.tick(board.player(), time.delta());
+}
+
+fn update_clocks(
+    board: Res<Board>,
+    clock: Res<Clock>,
+    orientation: Res<Orientation>,
+    mut texts: Query<(&mut Text, &mut Style, &ClockText)>,
+) {
+    texts
+        .iter_mut()
+        .for_each(|(mut text, mut style, clock_text)| {
+            // Each player's clock sits on their own side of the board, past their captured pieces.
+            (style.top, style.bottom) = if clock_text.0 == orientation.bottom {
+                (Val::Auto, Val::Px(80.0))
+            } else {
+                (Val::Px(80.0), Val::Auto)
+            };
+
+            text.sections[0].value = clock
+                .remaining(&clock_text.0)
+                .map(format_time)
+                .unwrap_or_default();
+            text.sections[0].style.color = if clock.flagged() == Some(&clock_text.0) {
+                FLAGGED_COLOUR
+            } else if board.player() == &clock_text.0 {
+                ACTIVE_COLOUR
+            } else {
+                INACTIVE_COLOUR
+            };
+        });
+}
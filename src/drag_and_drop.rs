@@ -16,146 +16,280 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 //! A module for the drag and drop plugin.
+//!
+//! Pieces can either be dragged onto their destination, or clicked and then
+//! moved by clicking the destination square.
 
-use crate::board::*;
+use crate::{board::*, game_state::GameState, orientation::Orientation};
 use bevy::prelude::*;
 
+type PieceQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static mut Transform,
+        &'static Position,
+        Option<&'static Square>,
+    ),
+>;
+
 /// A plugin implementing a drag and drop system.
 pub struct DragAndDropPlugin;
 
 impl Plugin for DragAndDropPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_systems(Update, drag_and_drop);
+        app.add_event::<IllegalMove>()
+            .init_resource::<CursorState>()
+            .add_systems(Update, drag_and_drop.run_if(in_state(GameState::InGame)))
+            .add_systems(OnExit(GameState::InGame), reset_cursor_state);
     }
 }
 
-#[derive(Default, Component)]
-struct CursorState {
+/// An event sent when the player attempts a move that is not allowed.
+#[derive(Event)]
+pub struct IllegalMove;
+
+/// A marker component for the currently selected piece.
+#[derive(Component)]
+pub struct Selected;
+
+#[derive(Default, Resource)]
+pub(crate) struct CursorState {
     position: Vec2,
     piece: Option<(Entity, Vec3)>,
+    selected: Option<Entity>,
+    was_selected: bool,
 }
 
-fn drag_and_drop(
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn drag_and_drop(
     mut board: ResMut<Board>,
     mut commands: Commands,
-    mut cursor_state: Local<CursorState>,
+    mut cursor_state: ResMut<CursorState>,
     mouse_inputs: Res<Input<MouseButton>>,
     mut moved_events: EventReader<CursorMoved>,
-    mut query: Query<(Entity, &mut Transform, &mut Position, Option<&Square>)>,
-    mut windows: Query<&mut Window>,
+    mut query: PieceQuery,
+    mut illegal_moves: EventWriter<IllegalMove>,
+    orientation: Res<Orientation>,
+    windows: Query<&Window>,
 ) {
+    let window = windows.single();
+
     if let Some(cursor_event) = moved_events.read().last() {
-        let window = windows.single_mut();
         let window_centre = Vec2::new(window.width() / 2.0, window.height() / 2.0);
         cursor_state.position.x = cursor_event.position.x - window_centre.x;
         cursor_state.position.y = -(cursor_event.position.y - window_centre.y);
     };
 
-    if cursor_state.piece.is_some() {
+    // The held sprite is no longer a piece if the board changed underneath it.
+    if cursor_state
+        .piece
+        .is_some_and(|(piece, _)| query.get(piece).is_err())
+    {
+        cursor_state.piece = None;
+    }
+
+    if let Some((piece, offset)) = cursor_state.piece {
         if mouse_inputs.just_released(MouseButton::Left) {
-            let mut closest_square: Option<Entity> = None;
-            let mut closest_piece: Option<Entity> = None;
-
-            query.iter().for_each(|(entity, transform, _, piece)| {
-                if piece.is_none() {
-                    let diff = cursor_to_piece_diff(&cursor_state.position, &transform.translation);
-                    if diff.length() < (transform.scale.x / 2.0) {
-                        closest_square = Some(entity);
-                    }
-                }
-                if piece.is_some() {
-                    let diff = cursor_to_piece_diff(&cursor_state.position, &transform.translation);
-                    if diff.length() < (transform.scale.x / 2.0)
-                        && entity != cursor_state.piece.unwrap().0
-                    {
-                        closest_piece = Some(entity);
-                    }
-                }
-            });
-            let piece = cursor_state.piece.unwrap();
-            let piece_size = query.get(piece.0).unwrap().1.scale;
+            let old_pos = *query.get(piece).unwrap().2;
 
-            let closest_square = match closest_square {
-                Some(square) => square,
-                None => {
-                    let (_, mut piece_pos, piece_coord, _) = query.get_mut(piece.0).unwrap();
-                    let window = windows.single_mut();
-                    piece_pos.translation.x = piece_coord.x as f32 * piece_size.x
-                        - window.width() / 2.0
-                        + (piece_size.x / 2.0);
-                    piece_pos.translation.y = piece_coord.y as f32 * piece_size.y
-                        - window.height() / 2.0
-                        + (piece_size.y / 2.0);
-                    cursor_state.piece = None;
-                    return;
+            match square_at_cursor(&cursor_state.position, &query) {
+                // Dropped onto another square, so the piece is moved there.
+                Some(new_pos) if new_pos != old_pos => {
+                    move_piece(
+                        &mut board,
+                        &mut query,
+                        &mut illegal_moves,
+                        &orientation,
+                        window,
+                        piece,
+                        new_pos,
+                    );
+                    deselect(&mut commands, &mut cursor_state);
                 }
-            };
-
-            let piece_coord = query.get(piece.0).unwrap().2;
-            let closest_square_coord = query.get(closest_square).unwrap().2;
-            let boilerplate = *closest_square_coord;
-
-            match board.move_piece(Move {
-                old_pos: *piece_coord,
-                new_pos: *closest_square_coord,
-            }) {
-                Ok(_) => {
-                    let window = windows.single_mut();
-                    let (_, mut piece_pos, mut piece_coord, _) = query.get_mut(piece.0).unwrap();
-                    piece_coord.x = boilerplate.x;
-                    piece_coord.y = boilerplate.y;
-                    piece_pos.translation.x = boilerplate.x as f32 * piece_size.x
-                        - window.width() / 2.0
-                        + (piece_size.x / 2.0);
-                    piece_pos.translation.y = boilerplate.y as f32 * piece_size.y
-                        - window.height() / 2.0
-                        + (piece_size.y / 2.0);
-                    if let Some(closest_piece) = closest_piece {
-                        commands.entity(closest_piece).despawn();
+                // Released on its own square, so the piece was clicked rather than dragged.
+                Some(_) => {
+                    let (_, mut transform, position, _) = query.get_mut(piece).unwrap();
+                    place_piece(&board, &mut transform, position, &orientation, window);
+                    if cursor_state.was_selected {
+                        deselect(&mut commands, &mut cursor_state);
                     }
                 }
-                Err(err) => {
-                    let (_, mut piece_pos, piece_coord, _) = query.get_mut(piece.0).unwrap();
-                    eprintln!("{}", err);
-                    let window = windows.single_mut();
-                    piece_pos.translation.x = piece_coord.x as f32 * piece_size.x
-                        - window.width() / 2.0
-                        + (piece_size.x / 2.0);
-                    piece_pos.translation.y = piece_coord.y as f32 * piece_size.y
-                        - window.height() / 2.0
-                        + (piece_size.y / 2.0);
+                None => {
+                    let (_, mut transform, position, _) = query.get_mut(piece).unwrap();
+                    place_piece(&board, &mut transform, position, &orientation, window);
+                    deselect(&mut commands, &mut cursor_state);
                 }
             }
 
-            let mut piece_pos = query.get_mut(piece.0).unwrap().1;
-            piece_pos.translation.z = 0.5;
+            let mut transform = query.get_mut(piece).unwrap().1;
+            transform.translation.z = 0.5;
 
             cursor_state.piece = None;
             return;
         }
 
         if mouse_inputs.pressed(MouseButton::Left) {
-            let piece = cursor_state.piece.unwrap();
-            let mut transform = query.get_mut(piece.0).unwrap().1;
-            transform.translation.x = cursor_state.position.x + piece.1.x;
-            transform.translation.y = cursor_state.position.y + piece.1.y;
+            let mut transform = query.get_mut(piece).unwrap().1;
+            transform.translation.x = cursor_state.position.x + offset.x;
+            transform.translation.y = cursor_state.position.y + offset.y;
             return;
         }
     }
 
     if mouse_inputs.just_pressed(MouseButton::Left) {
-        query
-            .iter_mut()
-            .for_each(|(entity, mut transform, _, piece)| {
-                if piece.is_some() {
-                    let diff = cursor_to_piece_diff(&cursor_state.position, &transform.translation);
-                    if diff.length() < (transform.scale.x / 2.0) {
-                        cursor_state.piece = Some((entity, diff));
-                        transform.translation.z = 1.0;
+        let clicked_piece = query.iter().find_map(|(entity, transform, _, piece)| {
+            let diff = cursor_to_piece_diff(&cursor_state.position, &transform.translation);
+            match piece {
+                Some(square) if diff.length() < (transform.scale.x / 2.0) => {
+                    Some((entity, *square))
+                }
+                _ => None,
+            }
+        });
+
+        // The selected sprite is no longer a piece if it was captured since it was selected.
+        if cursor_state
+            .selected
+            .is_some_and(|selected| query.get(selected).is_err())
+        {
+            cursor_state.selected = None;
+        }
+
+        if let Some(selected) = cursor_state.selected {
+            let selected_square = *query.get(selected).unwrap().3.unwrap();
+
+            match clicked_piece {
+                // Clicking a piece of the same colour picks that piece up instead.
+                Some((entity, square)) if square.colour() == selected_square.colour() => {
+                    if entity != selected {
+                        deselect(&mut commands, &mut cursor_state);
+                    }
+                }
+                _ => {
+                    if let Some(new_pos) = square_at_cursor(&cursor_state.position, &query) {
+                        move_piece(
+                            &mut board,
+                            &mut query,
+                            &mut illegal_moves,
+                            &orientation,
+                            window,
+                            selected,
+                            new_pos,
+                        );
                     }
+                    deselect(&mut commands, &mut cursor_state);
+                    return;
                 }
-            });
+            }
+        }
+
+        if let Some((entity, _)) = clicked_piece {
+            let mut transform = query.get_mut(entity).unwrap().1;
+            let diff = cursor_to_piece_diff(&cursor_state.position, &transform.translation);
+            transform.translation.z = 1.0;
+            cursor_state.piece = Some((entity, diff));
+            cursor_state.was_selected = cursor_state.selected == Some(entity);
+            if !cursor_state.was_selected {
+                commands.entity(entity).insert(Selected);
+                cursor_state.selected = Some(entity);
+            }
+        }
+    }
+}
+
+/// Returns the position of the square underneath the cursor, if there is one.
+fn square_at_cursor(cursor_pos: &Vec2, query: &PieceQuery) -> Option<Position> {
+    query
+        .iter()
+        .find(|(_, transform, _, piece)| {
+            piece.is_none()
+                && cursor_to_piece_diff(cursor_pos, &transform.translation).length()
+                    < (transform.scale.x / 2.0)
+        })
+        .map(|(_, _, position, _)| *position)
+}
+
+/// Attempts to move a piece, returning its sprite to its square if the move is invalid.
+///
+/// The sprites of a valid move are left to the pieces plugin to reconcile.
+fn move_piece(
+    board: &mut Board,
+    query: &mut PieceQuery,
+    illegal_moves: &mut EventWriter<IllegalMove>,
+    orientation: &Orientation,
+    window: &Window,
+    piece: Entity,
+    new_pos: Position,
+) {
+    let (_, mut transform, position, _) = query.get_mut(piece).unwrap();
+
+    match board.move_piece(Move {
+        old_pos: *position,
+        new_pos,
+    }) {
+        Ok(_) => return,
+        Err(err) => eprintln!("{}", err),
+    }
+
+    illegal_moves.send(IllegalMove);
+    place_piece(board, &mut transform, position, orientation, window);
+}
+
+/// Moves a piece sprite onto the centre of its square.
+fn place_piece(
+    board: &Board,
+    transform: &mut Transform,
+    position: &Position,
+    orientation: &Orientation,
+    window: &Window,
+) {
+    let translation = orientation.translation(
+        board,
+        position,
+        transform.scale.x,
+        Vec2::new(window.width(), window.height()),
+    );
+    transform.translation.x = translation.x;
+    transform.translation.y = translation.y;
+}
+
+fn deselect(commands: &mut Commands, cursor_state: &mut CursorState) {
+    if let Some(selected) = cursor_state.selected.take() {
+        if let Some(mut entity) = commands.get_entity(selected) {
+            entity.remove::<Selected>();
+        }
+    }
+}
+
+/// Drops the held and selected pieces, so that they do not carry over into the next game.
+fn reset_cursor_state(
+    board: Res<Board>,
+    mut commands: Commands,
+    mut cursor_state: ResMut<CursorState>,
+    mut query: PieceQuery,
+    orientation: Res<Orientation>,
+    selected: Query<Entity, With<Selected>>,
+    windows: Query<&Window>,
+) {
+    // A held piece would otherwise stay at the cursor, above the screens shown next.
+    if let Some((piece, _)) = cursor_state.piece.take() {
+        if let Ok((_, mut transform, position, _)) = query.get_mut(piece) {
+            place_piece(
+                &board,
+                &mut transform,
+                position,
+                &orientation,
+                windows.single(),
+            );
+            transform.translation.z = 0.5;
+        }
     }
+    cursor_state.selected = None;
+    selected.iter().for_each(|entity| {
+        commands.entity(entity).remove::<Selected>();
+    });
 }
 
 fn cursor_to_piece_diff(cursor_pos: &Vec2, piece_pos: &Vec3) -> Vec3 {
@@ -0,0 +1,186 @@
+// Copyright (C) 2023  Brynley Llewellyn-Roux and Aryan Jassal
+//
+// This file is part of chess.
+//
+// chess is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published
+// by the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// chess is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A module for the game state plugin.
+
+use crate::{
+    board::*,
+    clock::{Clock, TimeControl},
+    orientation::Orientation,
+};
+use bevy::prelude::*;
+use std::fmt;
+
+/// A plugin implementing the flow between the menus and the game.
+pub struct GameStatePlugin;
+
+impl Plugin for GameStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state::<GameState>()
+            .add_event::<NewGame>()
+            .init_resource::<GameSetup>()
+            .add_systems(OnEnter(GameState::InGame), start_game)
+            .add_systems(
+                Update,
+                (detect_game_over, leave_game).run_if(in_state(GameState::InGame)),
+            );
+    }
+}
+
+/// An enum representing the screens the app can be on.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, States)]
+pub enum GameState {
+    /// The main menu.
+    #[default]
+    MainMenu,
+    /// The screen for configuring a new game.
+    Setup,
+    /// The screen for changing the theme.
+    Settings,
+    /// A game in progress.
+    InGame,
+    /// The screen shown once a game has ended.
+    GameOver,
+}
+
+/// An event sent when a game starts and the board is replaced rather than moved on.
+#[derive(Event)]
+pub struct NewGame;
+
+/// An enum representing who the players of a game are.
+///
+/// Only hot-seat games are supported for now; playing against an engine or
+/// over a network will be added as further modes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GameMode {
+    /// Two players taking turns on the same computer.
+    #[default]
+    HotSeat,
+}
+
+impl fmt::Display for GameMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameMode::HotSeat => write!(f, "Hot seat"),
+        }
+    }
+}
+
+/// An enum representing the rules a game is played under.
+///
+/// Only standard chess is supported for now.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Variant {
+    /// The standard rules of chess.
+    #[default]
+    Standard,
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Variant::Standard => write!(f, "Standard"),
+        }
+    }
+}
+
+/// A resource representing the configuration of the next game.
+///
+/// # Examples
+///
+/// ```
+/// use chess::{
+///     board::PieceColour,
+///     game_state::{GameMode, GameSetup},
+/// };
+///
+/// let setup = GameSetup::default();
+/// assert_eq!(setup.mode, GameMode::HotSeat);
+/// assert_eq!(setup.colour, PieceColour::White);
+/// assert!(setup.board().is_ok());
+/// ```
+#[derive(Resource)]
+pub struct GameSetup {
+    /// Who the players are.
+    pub mode: GameMode,
+    /// The rules the game is played under.
+    pub variant: Variant,
+    /// The colour drawn at the bottom of the board when the game starts.
+    pub colour: PieceColour,
+    /// Whether the board is flipped towards the player to move after every move.
+    pub auto_flip: bool,
+    /// The starting position in Forsyth-Edwards Notation, or empty for the standard position.
+    pub fen: String,
+    /// The time control of the players' clocks.
+    pub time_control: TimeControl,
+}
+
+impl Default for GameSetup {
+    fn default() -> Self {
+        Self {
+            mode: GameMode::HotSeat,
+            variant: Variant::Standard,
+            colour: PieceColour::White,
+            auto_flip: false,
+            fen: String::new(),
+            time_control: TimeControl::Untimed,
+        }
+    }
+}
+
+impl GameSetup {
+    /// Creates the chessboard the game starts with.
+    ///
+    /// Returns an error if the starting position is not valid.
+    pub fn board(&self) -> Result<Board, &'static str> {
+        if self.fen.trim().is_empty() {
+            Ok(Board::default())
+        } else {
+            Board::from_fen(&self.fen)
+        }
+    }
+}
+
+fn start_game(
+    setup: Res<GameSetup>,
+    mut board: ResMut<Board>,
+    mut clock: ResMut<Clock>,
+    mut orientation: ResMut<Orientation>,
+    mut new_games: EventWriter<NewGame>,
+) {
+    *board = setup.board().unwrap_or_default();
+    *clock = Clock::new(setup.time_control.clone());
+    orientation.bottom = setup.colour;
+    orientation.auto_flip = setup.auto_flip;
+    new_games.send(NewGame);
+}
+
+fn detect_game_over(
+    board: Res<Board>,
+    clock: Res<Clock>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if (board.is_changed() && board.outcome().is_some()) || clock.flagged().is_some() {
+        next_state.set(GameState::GameOver);
+    }
+}
+
+fn leave_game(keys: Res<Input<KeyCode>>, mut next_state: ResMut<NextState<GameState>>) {
+    if keys.just_pressed(KeyCode::Escape) {
+        next_state.set(GameState::MainMenu);
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright (C) 2023  Brynley Llewellyn-Roux and Aryan Jassal
+//
+// This file is part of chess.
+//
+// chess is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published
+// by the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// chess is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A module for the highlights plugin.
+
+use crate::{board::*, drag_and_drop::Selected};
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+
+const QUIET_COLOUR: Color = Color::rgba(0.0, 0.0, 0.0, 0.2);
+const CAPTURE_COLOUR: Color = Color::rgba(0.8, 0.1, 0.1, 0.4);
+const SELECTED_COLOUR: Color = Color::rgba(0.1, 0.5, 0.9, 0.4);
+const LAST_MOVE_COLOUR: Color = Color::rgba(0.9, 0.8, 0.1, 0.4);
+const CHECK_COLOUR: Color = Color::rgba(0.9, 0.0, 0.0, 0.6);
+
+/// A plugin implementing highlighting of squares on the chessboard.
+pub struct HighlightsPlugin;
+
+impl Plugin for HighlightsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_highlights)
+            .add_systems(Update, (highlight_valid_moves, highlight_board_state));
+    }
+}
+
+#[derive(Component)]
+struct MoveHighlight;
+
+#[derive(Component)]
+struct StateHighlight;
+
+#[derive(Resource)]
+struct HighlightAssets {
+    dot_mesh: Handle<Mesh>,
+    square_mesh: Handle<Mesh>,
+    quiet_material: Handle<ColorMaterial>,
+    capture_material: Handle<ColorMaterial>,
+    selected_material: Handle<ColorMaterial>,
+    last_move_material: Handle<ColorMaterial>,
+    check_material: Handle<ColorMaterial>,
+}
+
+fn setup_highlights(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.insert_resource(HighlightAssets {
+        dot_mesh: meshes.add(Mesh::from(shape::Circle::new(0.15))),
+        square_mesh: meshes.add(Mesh::from(shape::Quad::new(Vec2::splat(1.0)))),
+        quiet_material: materials.add(ColorMaterial::from(QUIET_COLOUR)),
+        capture_material: materials.add(ColorMaterial::from(CAPTURE_COLOUR)),
+        selected_material: materials.add(ColorMaterial::from(SELECTED_COLOUR)),
+        last_move_material: materials.add(ColorMaterial::from(LAST_MOVE_COLOUR)),
+        check_material: materials.add(ColorMaterial::from(CHECK_COLOUR)),
+    });
+}
+
+fn highlight_valid_moves(
+    board: Res<Board>,
+    mut commands: Commands,
+    assets: Res<HighlightAssets>,
+    selected: Query<&Position, Added<Selected>>,
+    mut deselected: RemovedComponents<Selected>,
+    highlights: Query<Entity, With<MoveHighlight>>,
+    squares: Query<(Entity, &Position), Without<Square>>,
+) {
+    if deselected.read().count() > 0 {
+        highlights
+            .iter()
+            .for_each(|entity| commands.entity(entity).despawn_recursive());
+    }
+
+    selected.iter().for_each(|position| {
+        let mut targets = vec![(*position, &assets.square_mesh, &assets.selected_material)];
+        board.valid_moves(position).iter().for_each(|piece_move| {
+            targets.push(
+                match board.layout()[piece_move.new_pos.y][piece_move.new_pos.x] {
+                    Square::Empty => (piece_move.new_pos, &assets.dot_mesh, &assets.quiet_material),
+                    Square::Piece { .. } => (
+                        piece_move.new_pos,
+                        &assets.square_mesh,
+                        &assets.capture_material,
+                    ),
+                },
+            );
+        });
+
+        targets.iter().for_each(|(position, mesh, material)| {
+            if let Some((square, _)) = squares
+                .iter()
+                .find(|(_, square_pos)| *square_pos == position)
+            {
+                commands.entity(square).with_children(|parent| {
+                    parent.spawn((
+                        MaterialMesh2dBundle {
+                            mesh: (*mesh).clone().into(),
+                            material: (*material).clone(),
+                            transform: Transform::from_xyz(0.0, 0.0, 0.2),
+                            ..default()
+                        },
+                        MoveHighlight,
+                    ));
+                });
+            }
+        });
+    });
+}
+
+fn highlight_board_state(
+    board: Res<Board>,
+    mut commands: Commands,
+    assets: Res<HighlightAssets>,
+    highlights: Query<Entity, With<StateHighlight>>,
+    squares: Query<(Entity, &Position), Without<Square>>,
+) {
+    if !board.is_changed() {
+        return;
+    }
+
+    highlights
+        .iter()
+        .for_each(|entity| commands.entity(entity).despawn_recursive());
+
+    let mut targets = vec![];
+    if let Some(last_move) = board.move_list().last() {
+        targets.push((last_move.old_pos, &assets.last_move_material));
+        targets.push((last_move.new_pos, &assets.last_move_material));
+    }
+    [PieceColour::White, PieceColour::Black]
+        .iter()
+        .filter(|colour| board.is_check(colour))
+        .filter_map(|colour| board.king_position(colour))
+        .for_each(|king_pos| targets.push((king_pos, &assets.check_material)));
+
+    targets.iter().for_each(|(position, material)| {
+        if let Some((square, _)) = squares
+            .iter()
+            .find(|(_, square_pos)| *square_pos == position)
+        {
+            commands.entity(square).with_children(|parent| {
+                parent.spawn((
+                    MaterialMesh2dBundle {
+                        mesh: assets.square_mesh.clone().into(),
+                        material: (*material).clone(),
+                        transform: Transform::from_xyz(0.0, 0.0, 0.1),
+                        ..default()
+                    },
+                    StateHighlight,
+                ));
+            });
+        }
+    });
+}
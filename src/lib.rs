@@ -20,5 +20,18 @@
 #![warn(missing_docs)]
 pub mod board;
 
+pub mod animation;
+pub mod captured_pieces;
+pub mod clock;
 pub mod drag_and_drop;
+pub mod game_state;
+pub mod highlights;
+pub mod menu;
+pub mod move_list;
+pub mod orientation;
+pub mod pieces;
+pub mod solver;
+pub mod sound;
+pub mod tablebase;
+pub mod theme;
 pub mod update_dimensions;
@@ -19,18 +19,27 @@
 use bevy::{
     diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
     prelude::*,
-    sprite::MaterialMesh2dBundle,
+    sprite::{Anchor, MaterialMesh2dBundle},
     window::{PresentMode, WindowMode},
 };
 use chess::{
-    board::{Board, PieceColour, PieceKind, Position, Square},
+    animation::AnimationPlugin,
+    board::{Board, Position},
+    captured_pieces::CapturedPiecesPlugin,
+    clock::ClockPlugin,
     drag_and_drop::DragAndDropPlugin,
-    update_dimensions::UpdateDimensionsPlugin,
+    game_state::GameStatePlugin,
+    highlights::HighlightsPlugin,
+    menu::MenuPlugin,
+    move_list::MoveListPlugin,
+    orientation::{Orientation, OrientationPlugin},
+    pieces::PiecesPlugin,
+    sound::SoundPlugin,
+    theme::{Theme, ThemePlugin},
+    update_dimensions::{square_size, CoordinateLabel, UpdateDimensionsPlugin},
 };
 
-const LIGHT_COLOUR: Color = Color::rgb(0.93, 0.93, 0.82);
-const DARK_COLOUR: Color = Color::rgb(0.46, 0.59, 0.34);
-const SQUARE_SIZE: f32 = 64.0;
+const LABEL_COLOUR: Color = Color::rgba(0.0, 0.0, 0.0, 0.5);
 
 #[derive(Component)]
 struct PlayerText;
@@ -50,21 +59,36 @@ fn main() {
             ..Default::default()
         }))
         .init_resource::<Board>()
+        .add_plugins(AnimationPlugin)
+        .add_plugins(CapturedPiecesPlugin)
+        .add_plugins(ClockPlugin)
         .add_plugins(DragAndDropPlugin)
+        .add_plugins(GameStatePlugin)
+        .add_plugins(HighlightsPlugin)
+        .add_plugins(MenuPlugin)
+        .add_plugins(MoveListPlugin)
+        .add_plugins(OrientationPlugin)
+        .add_plugins(PiecesPlugin)
+        .add_plugins(SoundPlugin)
+        .add_plugins(ThemePlugin)
         .add_plugins(UpdateDimensionsPlugin)
-        .add_plugins(FrameTimeDiagnosticsPlugin::default())
+        .add_plugins(FrameTimeDiagnosticsPlugin)
         .add_systems(Startup, setup)
         .add_systems(Update, update_player_text)
         .add_systems(Update, update_fps_counter)
         .run();
 }
 
+#[allow(clippy::too_many_arguments)]
 fn setup(
     mut commands: Commands,
     board: Res<Board>,
+    theme: Res<Theme>,
+    orientation: Res<Orientation>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     asset_server: Res<AssetServer>,
+    windows: Query<&Window>,
 ) {
     commands
         .spawn(
@@ -122,8 +146,13 @@ fn setup(
         )
         .insert(FpsText);
 
+    let window = windows.single();
+    let window_size = Vec2::new(window.width(), window.height());
+    let size = square_size(&board, window_size);
+
     board.layout().iter().enumerate().for_each(|(y, rank)| {
-        rank.iter().enumerate().for_each(|(x, square)| {
+        rank.iter().enumerate().for_each(|(x, _)| {
+            let position = Position { x, y };
             commands
                 .spawn(MaterialMesh2dBundle {
                     mesh: meshes
@@ -132,65 +161,51 @@ fn setup(
                             flip: false,
                         }))
                         .into(),
-                    material: materials.add(ColorMaterial::from(if (y + x) % 2 == 0 {
-                        DARK_COLOUR
-                    } else {
-                        LIGHT_COLOUR
-                    })),
-                    transform: Transform::from_xyz(
-                        x as f32 * SQUARE_SIZE,
-                        y as f32 * SQUARE_SIZE,
-                        0.0,
+                    material: materials.add(ColorMaterial::from(theme.board.square(&position))),
+                    transform: Transform::from_translation(
+                        orientation
+                            .translation(&board, &position, size, window_size)
+                            .extend(0.0),
                     )
-                    .with_scale(Vec3::splat(SQUARE_SIZE)),
+                    .with_scale(Vec3::new(size, size, 1.0)),
                     ..default()
                 })
-                .insert(Position { x, y });
-
-            let piece_texture = match square {
-                Square::Empty => return,
-                Square::Piece {
-                    piece_kind: piece_type,
-                    piece_colour,
-                } => match piece_colour {
-                    PieceColour::Black => match piece_type {
-                        PieceKind::King => asset_server.load("../assets/bk.png"),
-                        PieceKind::Pawn => asset_server.load("../assets/bp.png"),
-                        PieceKind::Bishop => asset_server.load("../assets/bb.png"),
-                        PieceKind::Knight => asset_server.load("../assets/bn.png"),
-                        PieceKind::Rook => asset_server.load("../assets/br.png"),
-                        PieceKind::Queen => asset_server.load("../assets/bq.png"),
-                    },
-                    PieceColour::White => match piece_type {
-                        PieceKind::King => asset_server.load("../assets/wk.png"),
-                        PieceKind::Pawn => asset_server.load("../assets/wp.png"),
-                        PieceKind::Bishop => asset_server.load("../assets/wb.png"),
-                        PieceKind::Knight => asset_server.load("../assets/wn.png"),
-                        PieceKind::Rook => asset_server.load("../assets/wr.png"),
-                        PieceKind::Queen => asset_server.load("../assets/wq.png"),
-                    },
-                },
-            };
+                .insert(position);
+        })
+    });
 
-            commands
-                .spawn(MaterialMesh2dBundle {
-                    mesh: meshes
-                        .add(Mesh::from(shape::Quad {
-                            size: Vec2::splat(1.0),
-                            flip: false,
-                        }))
-                        .into(),
-                    material: materials.add(ColorMaterial::from(piece_texture)),
-                    transform: Transform::from_xyz(
-                        x as f32 * SQUARE_SIZE,
-                        y as f32 * SQUARE_SIZE,
-                        0.5,
-                    )
-                    .with_scale(Vec3::splat(SQUARE_SIZE)),
+    (0..board.layout().len()).for_each(|i| {
+        [
+            (
+                ((b'a' + i as u8) as char).to_string(),
+                CoordinateLabel::File(i),
+                Anchor::BottomRight,
+            ),
+            (
+                (board.layout().len() - i).to_string(),
+                CoordinateLabel::Rank(i),
+                Anchor::TopLeft,
+            ),
+        ]
+        .into_iter()
+        .for_each(|(label, coordinate, anchor)| {
+            commands.spawn((
+                Text2dBundle {
+                    text: Text::from_section(
+                        label,
+                        TextStyle {
+                            font: asset_server.load("fonts/text.ttf"),
+                            font_size: 16.0,
+                            color: LABEL_COLOUR,
+                        },
+                    ),
+                    text_anchor: anchor,
+                    transform: Transform::from_xyz(0.0, 0.0, 0.3),
                     ..default()
-                })
-                .insert((Position { x, y }, *square));
-        })
+                },
+                coordinate,
+            ));
+        });
     });
 
     commands.spawn(Camera2dBundle::default());
@@ -0,0 +1,480 @@
+// Copyright (C) 2023  Brynley Llewellyn-Roux and Aryan Jassal
+//
+// This file is part of chess.
+//
+// chess is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published
+// by the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// chess is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A module for the menu plugin.
+
+use crate::{
+    animation::AnimationSettings,
+    board::*,
+    clock::Clock,
+    game_state::{GameSetup, GameState},
+    sound::SoundSettings,
+    theme::{piece_sets, Theme},
+};
+use bevy::{app::AppExit, prelude::*};
+
+const BACKGROUND_COLOUR: Color = Color::rgba(0.0, 0.0, 0.0, 0.8);
+const BUTTON_COLOUR: Color = Color::rgb(0.15, 0.15, 0.15);
+const HOVERED_BUTTON_COLOUR: Color = Color::rgb(0.25, 0.25, 0.25);
+const PRESSED_BUTTON_COLOUR: Color = Color::rgb(0.46, 0.59, 0.34);
+const ERROR_COLOUR: Color = Color::rgb(0.9, 0.3, 0.3);
+
+type ButtonQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static Interaction, &'static mut BackgroundColor),
+    (Changed<Interaction>, With<MenuButton>),
+>;
+
+/// A plugin implementing the main menu, game setup, settings and game over screens.
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::MainMenu), spawn_main_menu)
+            .add_systems(OnExit(GameState::MainMenu), despawn_screen)
+            .add_systems(OnEnter(GameState::Setup), spawn_setup)
+            .add_systems(OnExit(GameState::Setup), despawn_screen)
+            .add_systems(OnEnter(GameState::Settings), spawn_settings)
+            .add_systems(OnExit(GameState::Settings), despawn_screen)
+            .add_systems(OnEnter(GameState::GameOver), spawn_game_over)
+            .add_systems(OnExit(GameState::GameOver), despawn_screen)
+            .add_systems(Update, (handle_buttons, colour_buttons))
+            .add_systems(
+                Update,
+                (edit_fen, update_setup_text)
+                    .chain()
+                    .run_if(in_state(GameState::Setup)),
+            )
+            .add_systems(
+                Update,
+                update_settings_text.run_if(in_state(GameState::Settings)),
+            );
+    }
+}
+
+/// A marker component for the root node of the current screen.
+#[derive(Component)]
+struct Screen;
+
+/// The action performed when a menu button is pressed.
+#[derive(Clone, Copy, Component)]
+enum MenuButton {
+    NewGame,
+    Settings,
+    Quit,
+    ToggleColour,
+    ToggleAutoFlip,
+    CycleTimeControl,
+    Start,
+    Rematch,
+    MainMenu,
+    CycleBoardTheme,
+    CyclePieceSet,
+    ToggleAnimationSpeed,
+    ToggleSound,
+    CycleVolume,
+}
+
+/// The parts of the setup screen that reflect the current `GameSetup`.
+#[derive(Component)]
+enum SetupText {
+    Mode,
+    Variant,
+    Colour,
+    AutoFlip,
+    TimeControl,
+    Fen,
+    Error,
+}
+
+/// The parts of the settings screen that reflect the current `Theme`, `AnimationSettings`
+/// and `SoundSettings`.
+#[derive(Component)]
+enum SettingsText {
+    BoardTheme,
+    PieceSet,
+    AnimationSpeed,
+    Sound,
+    Volume,
+}
+
+fn spawn_screen(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    title: String,
+    spawn_children: impl FnOnce(&mut ChildBuilder, &TextStyle),
+) {
+    let text_style = TextStyle {
+        font: asset_server.load("fonts/text.ttf"),
+        font_size: 24.0,
+        color: Color::WHITE,
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                background_color: BACKGROUND_COLOUR.into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            Screen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    title,
+                    TextStyle {
+                        font_size: 48.0,
+                        ..text_style.clone()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                }),
+            );
+            spawn_children(parent, &text_style);
+        });
+}
+
+fn spawn_button(
+    parent: &mut ChildBuilder,
+    text_style: &TextStyle,
+    label: &str,
+    action: MenuButton,
+) {
+    spawn_button_with(parent, text_style, label, action, ());
+}
+
+fn spawn_button_with(
+    parent: &mut ChildBuilder,
+    text_style: &TextStyle,
+    label: &str,
+    action: MenuButton,
+    label_bundle: impl Bundle,
+) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(320.0),
+                    height: Val::Px(50.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: BUTTON_COLOUR.into(),
+                ..default()
+            },
+            action,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(label, text_style.clone()),
+                label_bundle,
+            ));
+        });
+}
+
+fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    spawn_screen(
+        &mut commands,
+        &asset_server,
+        "Chess".to_string(),
+        |parent, text_style| {
+            spawn_button(parent, text_style, "New game", MenuButton::NewGame);
+            spawn_button(parent, text_style, "Settings", MenuButton::Settings);
+            spawn_button(parent, text_style, "Quit", MenuButton::Quit);
+        },
+    );
+}
+
+fn spawn_setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    spawn_screen(
+        &mut commands,
+        &asset_server,
+        "New game".to_string(),
+        |parent, text_style| {
+            // Only one mode and one variant exist so far, so they are not buttons yet.
+            parent.spawn((
+                TextBundle::from_section("", text_style.clone()),
+                SetupText::Mode,
+            ));
+            parent.spawn((
+                TextBundle::from_section("", text_style.clone()),
+                SetupText::Variant,
+            ));
+            spawn_button_with(
+                parent,
+                text_style,
+                "",
+                MenuButton::ToggleColour,
+                SetupText::Colour,
+            );
+            spawn_button_with(
+                parent,
+                text_style,
+                "",
+                MenuButton::ToggleAutoFlip,
+                SetupText::AutoFlip,
+            );
+            spawn_button_with(
+                parent,
+                text_style,
+                "",
+                MenuButton::CycleTimeControl,
+                SetupText::TimeControl,
+            );
+            parent.spawn(TextBundle::from_section(
+                "Starting position (type a FEN):",
+                text_style.clone(),
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 16.0,
+                        ..text_style.clone()
+                    },
+                ),
+                SetupText::Fen,
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 16.0,
+                        color: ERROR_COLOUR,
+                        ..text_style.clone()
+                    },
+                ),
+                SetupText::Error,
+            ));
+            spawn_button(parent, text_style, "Start", MenuButton::Start);
+            spawn_button(parent, text_style, "Back", MenuButton::MainMenu);
+        },
+    );
+}
+
+fn spawn_settings(mut commands: Commands, asset_server: Res<AssetServer>) {
+    spawn_screen(
+        &mut commands,
+        &asset_server,
+        "Settings".to_string(),
+        |parent, text_style| {
+            spawn_button_with(
+                parent,
+                text_style,
+                "",
+                MenuButton::CycleBoardTheme,
+                SettingsText::BoardTheme,
+            );
+            // Cycling through a single piece set would do nothing.
+            if piece_sets().len() > 1 {
+                spawn_button_with(
+                    parent,
+                    text_style,
+                    "",
+                    MenuButton::CyclePieceSet,
+                    SettingsText::PieceSet,
+                );
+            }
+            spawn_button_with(
+                parent,
+                text_style,
+                "",
+                MenuButton::ToggleAnimationSpeed,
+                SettingsText::AnimationSpeed,
+            );
+            spawn_button_with(
+                parent,
+                text_style,
+                "",
+                MenuButton::ToggleSound,
+                SettingsText::Sound,
+            );
+            spawn_button_with(
+                parent,
+                text_style,
+                "",
+                MenuButton::CycleVolume,
+                SettingsText::Volume,
+            );
+            spawn_button(parent, text_style, "Back", MenuButton::MainMenu);
+        },
+    );
+}
+
+fn spawn_game_over(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    board: Res<Board>,
+    clock: Res<Clock>,
+) {
+    let title = match board.outcome().or_else(|| clock.outcome(&board)) {
+        Some(outcome) => outcome.to_string(),
+        None => "Game over".to_string(),
+    };
+
+    spawn_screen(&mut commands, &asset_server, title, |parent, text_style| {
+        spawn_button(parent, text_style, "Rematch", MenuButton::Rematch);
+        spawn_button(parent, text_style, "Main menu", MenuButton::MainMenu);
+    });
+}
+
+fn despawn_screen(mut commands: Commands, screens: Query<Entity, With<Screen>>) {
+    screens
+        .iter()
+        .for_each(|entity| commands.entity(entity).despawn_recursive());
+}
+
+#[allow(clippy::too_many_arguments)]
+fn handle_buttons(
+    buttons: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    mut setup: ResMut<GameSetup>,
+    mut animation_settings: ResMut<AnimationSettings>,
+    mut theme: ResMut<Theme>,
+    mut sound_settings: ResMut<SoundSettings>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut exit: EventWriter<AppExit>,
+    mut texts: Query<(&mut Text, &SetupText)>,
+) {
+    buttons.iter().for_each(|(interaction, button)| {
+        if *interaction != Interaction::Pressed {
+            return;
+        }
+
+        match button {
+            MenuButton::NewGame => next_state.set(GameState::Setup),
+            MenuButton::Settings => next_state.set(GameState::Settings),
+            MenuButton::Quit => exit.send(AppExit),
+            MenuButton::ToggleColour => setup.colour = setup.colour.opponent(),
+            MenuButton::ToggleAutoFlip => setup.auto_flip = !setup.auto_flip,
+            MenuButton::CycleTimeControl => setup.time_control = setup.time_control.next(),
+            MenuButton::Start => match setup.board() {
+                Ok(_) => next_state.set(GameState::InGame),
+                Err(err) => texts
+                    .iter_mut()
+                    .filter(|(_, setup_text)| matches!(setup_text, SetupText::Error))
+                    .for_each(|(mut text, _)| text.sections[0].value = err.to_string()),
+            },
+            MenuButton::Rematch => next_state.set(GameState::InGame),
+            MenuButton::MainMenu => next_state.set(GameState::MainMenu),
+            MenuButton::CycleBoardTheme => theme.board = theme.board.next(),
+            MenuButton::CyclePieceSet => theme.next_piece_set(),
+            MenuButton::ToggleAnimationSpeed => {
+                animation_settings.speed = animation_settings.speed.next()
+            }
+            MenuButton::ToggleSound => sound_settings.muted = !sound_settings.muted,
+            MenuButton::CycleVolume => sound_settings.next_volume(),
+        }
+    });
+}
+
+fn colour_buttons(mut buttons: ButtonQuery) {
+    buttons
+        .iter_mut()
+        .for_each(|(interaction, mut background)| {
+            *background = match interaction {
+                Interaction::Pressed => PRESSED_BUTTON_COLOUR,
+                Interaction::Hovered => HOVERED_BUTTON_COLOUR,
+                Interaction::None => BUTTON_COLOUR,
+            }
+            .into();
+        });
+}
+
+fn edit_fen(
+    mut characters: EventReader<ReceivedCharacter>,
+    keys: Res<Input<KeyCode>>,
+    mut setup: ResMut<GameSetup>,
+) {
+    characters
+        .read()
+        .filter(|event| !event.char.is_control())
+        .for_each(|event| setup.fen.push(event.char));
+
+    if keys.just_pressed(KeyCode::Back) {
+        setup.fen.pop();
+    }
+}
+
+fn update_setup_text(
+    setup: Res<GameSetup>,
+    mut texts: Query<(&mut Text, &SetupText)>,
+    added: Query<(), Added<SetupText>>,
+) {
+    if !setup.is_changed() && added.is_empty() {
+        return;
+    }
+
+    texts.iter_mut().for_each(|(mut text, setup_text)| {
+        text.sections[0].value = match setup_text {
+            SetupText::Mode => format!("Mode: {}", setup.mode),
+            SetupText::Variant => format!("Variant: {}", setup.variant),
+            SetupText::Colour => format!("Play as: {:?}", setup.colour),
+            SetupText::AutoFlip => format!(
+                "Flip board every move: {}",
+                if setup.auto_flip { "On" } else { "Off" }
+            ),
+            SetupText::TimeControl => format!("Time control: {}", setup.time_control),
+            SetupText::Fen if setup.fen.is_empty() => "Standard position".to_string(),
+            SetupText::Fen => setup.fen.clone(),
+            SetupText::Error => String::new(),
+        }
+    });
+}
+
+fn update_settings_text(
+    theme: Res<Theme>,
+    animation_settings: Res<AnimationSettings>,
+    sound_settings: Res<SoundSettings>,
+    mut texts: Query<(&mut Text, &SettingsText)>,
+    added: Query<(), Added<SettingsText>>,
+) {
+    if !theme.is_changed()
+        && !animation_settings.is_changed()
+        && !sound_settings.is_changed()
+        && added.is_empty()
+    {
+        return;
+    }
+
+    texts.iter_mut().for_each(|(mut text, settings_text)| {
+        text.sections[0].value = match settings_text {
+            SettingsText::BoardTheme => format!("Board: {:?}", theme.board),
+            SettingsText::PieceSet => format!("Pieces: {}", theme.piece_set),
+            SettingsText::AnimationSpeed => {
+                format!("Animation speed: {:?}", animation_settings.speed)
+            }
+            SettingsText::Sound => {
+                format!("Sound: {}", if sound_settings.muted { "Off" } else { "On" })
+            }
+            SettingsText::Volume => format!("Volume: {:.0}%", sound_settings.volume * 100.0),
+        }
+    });
+}
@@ -0,0 +1,266 @@
+// Copyright (C) 2023  Brynley Llewellyn-Roux and Aryan Jassal
+//
+// This file is part of chess.
+//
+// chess is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published
+// by the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// chess is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A module for the move list plugin.
+
+use crate::{board::*, game_state::GameState, update_dimensions::SIDE_PANEL_WIDTH};
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+    ui::RelativeCursorPosition,
+};
+
+const PANEL_COLOUR: Color = Color::rgba(0.1, 0.1, 0.1, 0.8);
+const CURRENT_MOVE_COLOUR: Color = Color::rgb(0.46, 0.59, 0.34);
+const MOVE_COLOUR: Color = Color::WHITE;
+const UNDONE_MOVE_COLOUR: Color = Color::GRAY;
+const LINE_HEIGHT: f32 = 20.0;
+
+/// A plugin implementing a side panel listing the moves of the game.
+pub struct MoveListPlugin;
+
+impl Plugin for MoveListPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, setup_move_list).add_systems(
+            Update,
+            (
+                update_move_list,
+                select_move.run_if(in_state(GameState::InGame)),
+                scroll_move_list,
+            )
+                .chain(),
+        );
+    }
+}
+
+#[derive(Component)]
+struct MoveListPanel;
+
+#[derive(Component)]
+struct MoveListContent {
+    position: f32,
+    follow: bool,
+}
+
+/// The number of moves played once the move of an entry is made, or 0 for the starting position.
+#[derive(Component)]
+struct MoveEntry(usize);
+
+fn setup_move_list(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(15.0),
+                    right: Val::Px(15.0),
+                    bottom: Val::Px(45.0),
+                    width: Val::Px(SIDE_PANEL_WIDTH),
+                    flex_direction: FlexDirection::Column,
+                    overflow: Overflow::clip_y(),
+                    ..default()
+                },
+                background_color: PANEL_COLOUR.into(),
+                ..default()
+            },
+            RelativeCursorPosition::default(),
+            MoveListPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        width: Val::Percent(100.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                MoveListContent {
+                    position: 0.0,
+                    follow: true,
+                },
+            ));
+        });
+}
+
+fn update_move_list(
+    board: Res<Board>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut contents: Query<(Entity, &mut MoveListContent)>,
+) {
+    if !board.is_changed() {
+        return;
+    }
+
+    let (content, mut scroll) = contents.single_mut();
+    let san_list = board.san_list();
+    scroll.follow = board.ply() == san_list.len();
+
+    // Work out who made the first move, so that black moves stay in the right column.
+    let first_player = if board.ply().is_multiple_of(2) {
+        *board.player()
+    } else {
+        board.player().opponent()
+    };
+    let mut entries: Vec<Option<(usize, &String)>> = vec![];
+    if first_player == PieceColour::Black {
+        entries.push(None);
+    }
+    entries.extend(
+        san_list
+            .iter()
+            .enumerate()
+            .map(|(i, san)| Some((i + 1, san))),
+    );
+
+    let text_style = TextStyle {
+        font: asset_server.load("fonts/text.ttf"),
+        font_size: 16.0,
+        color: MOVE_COLOUR,
+    };
+
+    commands.entity(content).despawn_descendants();
+    commands.entity(content).with_children(|parent| {
+        // The starting position, so that every move can be taken back from the list.
+        parent
+            .spawn((
+                ButtonBundle {
+                    style: Style {
+                        height: Val::Px(LINE_HEIGHT),
+                        padding: UiRect::left(Val::Px(5.0)),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: if board.ply() == 0 {
+                        CURRENT_MOVE_COLOUR.into()
+                    } else {
+                        Color::NONE.into()
+                    },
+                    ..default()
+                },
+                MoveEntry(0),
+            ))
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section("Start", text_style.clone()));
+            });
+
+        entries.chunks(2).enumerate().for_each(|(row, pair)| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        height: Val::Px(LINE_HEIGHT),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(
+                        TextBundle::from_section(format!("{}.", row + 1), text_style.clone())
+                            .with_style(Style {
+                                width: Val::Px(40.0),
+                                padding: UiRect::left(Val::Px(5.0)),
+                                ..default()
+                            }),
+                    );
+
+                    pair.iter().for_each(|entry| {
+                        let (ply, san) = match entry {
+                            Some((ply, san)) => (*ply, san.as_str()),
+                            None => (0, "..."),
+                        };
+                        let mut entry = parent.spawn(ButtonBundle {
+                            style: Style {
+                                width: Val::Px(80.0),
+                                height: Val::Percent(100.0),
+                                padding: UiRect::left(Val::Px(5.0)),
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: if ply != 0 && ply == board.ply() {
+                                CURRENT_MOVE_COLOUR.into()
+                            } else {
+                                Color::NONE.into()
+                            },
+                            ..default()
+                        });
+                        if ply != 0 {
+                            entry.insert(MoveEntry(ply));
+                        }
+                        entry.with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                san,
+                                TextStyle {
+                                    color: if ply > board.ply() {
+                                        UNDONE_MOVE_COLOUR
+                                    } else {
+                                        MOVE_COLOUR
+                                    },
+                                    ..text_style.clone()
+                                },
+                            ));
+                        });
+                    });
+                });
+        });
+    });
+}
+
+fn select_move(
+    mut board: ResMut<Board>,
+    entries: Query<(&Interaction, &MoveEntry), Changed<Interaction>>,
+) {
+    entries.iter().for_each(|(interaction, entry)| {
+        if *interaction != Interaction::Pressed {
+            return;
+        }
+        while board.ply() > entry.0 && board.undo().is_ok() {}
+        while board.ply() < entry.0 && board.redo().is_ok() {}
+    });
+}
+
+fn scroll_move_list(
+    mut wheel_events: EventReader<MouseWheel>,
+    panels: Query<(&Node, &RelativeCursorPosition), With<MoveListPanel>>,
+    mut contents: Query<(&mut MoveListContent, &mut Style, &Node)>,
+) {
+    let (panel, cursor) = panels.single();
+    let (mut scroll, mut style, content) = contents.single_mut();
+    let max_scroll = (content.size().y - panel.size().y).max(0.0);
+
+    wheel_events.read().for_each(|event| {
+        if cursor.mouse_over() {
+            scroll.position += match event.unit {
+                MouseScrollUnit::Line => event.y * LINE_HEIGHT,
+                MouseScrollUnit::Pixel => event.y,
+            };
+            scroll.follow = false;
+        }
+    });
+
+    if scroll.follow {
+        scroll.position = -max_scroll;
+    }
+    scroll.position = scroll.position.clamp(-max_scroll, 0.0);
+
+    if style.top != Val::Px(scroll.position) {
+        style.top = Val::Px(scroll.position);
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright (C) 2023  Brynley Llewellyn-Roux and Aryan Jassal
+//
+// This file is part of chess.
+//
+// chess is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published
+// by the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// chess is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A module for the orientation plugin.
+//!
+//! Press `F` to flip the board, or `A` to toggle flipping the board towards
+//! the player to move after every move.
+
+use crate::{board::*, game_state::GameState};
+use bevy::prelude::*;
+
+/// A plugin implementing flipping of the chessboard.
+pub struct OrientationPlugin;
+
+impl Plugin for OrientationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Orientation>().add_systems(
+            Update,
+            (
+                toggle_orientation.run_if(in_state(GameState::InGame)),
+                auto_flip,
+            ),
+        );
+    }
+}
+
+/// A resource representing the orientation of the chessboard on screen.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::Vec2;
+/// use chess::{board::{Board, PieceColour, Position}, orientation::Orientation};
+///
+/// let mut orientation = Orientation::default();
+/// orientation.flip();
+/// assert_eq!(orientation.bottom, PieceColour::Black);
+///
+/// let translation = orientation.translation(
+///     &Board::default(),
+///     &Position { x: 0, y: 0 },
+///     64.0,
+///     Vec2::splat(512.0),
+/// );
+/// assert_eq!(translation, Vec2::new(224.0, -224.0));
+/// ```
+#[derive(Resource)]
+pub struct Orientation {
+    /// The colour whose pieces are drawn at the bottom of the screen.
+    pub bottom: PieceColour,
+    /// Whether the board is flipped towards the player to move after every move.
+    pub auto_flip: bool,
+}
+
+impl Default for Orientation {
+    fn default() -> Self {
+        Self {
+            bottom: PieceColour::White,
+            auto_flip: false,
+        }
+    }
+}
+
+impl Orientation {
+    /// Flips the board so that the other colour is at the bottom of the screen.
+    pub fn flip(&mut self) {
+        self.bottom = self.bottom.opponent();
+    }
+
+    /// Returns the world translation of the centre of a square.
+    ///
+    /// Takes in the chessboard, the position of the square, the size of a
+    /// square, and the size of the window the board is centred in.
+    pub fn translation(
+        &self,
+        board: &Board,
+        position: &Position,
+        square_size: f32,
+        window_size: Vec2,
+    ) -> Vec2 {
+        let last = board.layout().len() - 1;
+        let (column, row) = match self.bottom {
+            PieceColour::White => (position.x, last - position.y),
+            PieceColour::Black => (last - position.x, position.y),
+        };
+
+        Vec2::new(
+            column as f32 * square_size - window_size.x / 2.0 + (square_size / 2.0),
+            row as f32 * square_size - window_size.y / 2.0 + (square_size / 2.0),
+        )
+    }
+}
+
+fn toggle_orientation(keys: Res<Input<KeyCode>>, mut orientation: ResMut<Orientation>) {
+    if keys.just_pressed(KeyCode::F) {
+        orientation.flip();
+    }
+    if keys.just_pressed(KeyCode::A) {
+        orientation.auto_flip = !orientation.auto_flip;
+    }
+}
+
+fn auto_flip(board: Res<Board>, mut orientation: ResMut<Orientation>) {
+    if orientation.auto_flip
+        && (board.is_changed() || orientation.is_changed())
+        && &orientation.bottom != board.player()
+    {
+        orientation.bottom = *board.player();
+    }
+}
@@ -0,0 +1,287 @@
+// Copyright (C) 2023  Brynley Llewellyn-Roux and Aryan Jassal
+//
+// This file is part of chess.
+//
+// chess is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published
+// by the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// chess is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A module for the pieces plugin.
+
+use crate::{
+    animation::{AnimationSettings, FadeOut, Tween},
+    board::*,
+    game_state::NewGame,
+    orientation::Orientation,
+    theme::Theme,
+    update_dimensions::square_size,
+};
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle, transform::TransformSystem};
+
+/// A plugin implementing the spawning and animation of piece sprites from the chessboard.
+pub struct PiecesPlugin;
+
+impl Plugin for PiecesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, retexture_pieces).add_systems(
+            PostUpdate,
+            reconcile_pieces.before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Returns the asset path of the texture for a piece in a piece set.
+///
+/// # Examples
+///
+/// ```
+/// use chess::{board::{PieceColour, PieceKind}, pieces::texture_path};
+///
+/// assert_eq!(
+///     texture_path("standard", &PieceColour::White, &PieceKind::King),
+///     "pieces/standard/wk.png"
+/// );
+/// ```
+pub fn texture_path(piece_set: &str, piece_colour: &PieceColour, piece_kind: &PieceKind) -> String {
+    let colour = match piece_colour {
+        PieceColour::Black => "b",
+        PieceColour::White => "w",
+    };
+    let kind = match piece_kind {
+        PieceKind::King => "k",
+        PieceKind::Pawn => "p",
+        PieceKind::Bishop => "b",
+        PieceKind::Knight => "n",
+        PieceKind::Rook => "r",
+        PieceKind::Queen => "q",
+    };
+
+    format!("pieces/{piece_set}/{colour}{kind}.png")
+}
+
+/// The changes needed to bring a set of piece sprites in line with a chessboard.
+///
+/// Sprites are identified by `T`, which is an `Entity` in the game.
+#[derive(Debug, PartialEq)]
+pub struct SpriteChanges<T> {
+    /// Sprites whose piece moved, along with the square they moved to.
+    pub moved: Vec<(T, Position)>,
+    /// Pieces without a sprite, which need one spawned.
+    pub spawned: Vec<(Position, Square)>,
+    /// Sprites whose piece is no longer on the board.
+    pub removed: Vec<T>,
+}
+
+/// Compares the piece sprites against the layout of a chessboard.
+///
+/// An out of place sprite is matched with the closest square holding the same
+/// piece that has no sprite, so a piece that moved keeps its sprite.
+///
+/// # Examples
+///
+/// ```
+/// use chess::{
+///     board::{Board, Move, Position, Square},
+///     pieces::reconcile,
+/// };
+///
+/// let before = Board::default();
+/// let sprites: Vec<_> = (0..8)
+///     .flat_map(|y| (0..8).map(move |x| Position { x, y }))
+///     .map(|position| (position, before.layout()[position.y][position.x]))
+///     .filter(|(_, square)| *square != Square::Empty)
+///     .enumerate()
+///     .map(|(id, (position, square))| (id, position, square))
+///     .collect();
+///
+/// let mut after = Board::default();
+/// after.move_piece(Move {
+///     old_pos: Position { x: 4, y: 6 },
+///     new_pos: Position { x: 4, y: 4 },
+/// }).unwrap();
+///
+/// let changes = reconcile(&after, &sprites);
+/// assert_eq!(changes.moved.len(), 1);
+/// assert!(changes.spawned.is_empty());
+/// assert!(changes.removed.is_empty());
+/// ```
+pub fn reconcile<T: Copy>(board: &Board, sprites: &[(T, Position, Square)]) -> SpriteChanges<T> {
+    let layout = board.layout();
+    let mut stale: Vec<_> = sprites
+        .iter()
+        .filter(|(_, position, square)| layout[position.y][position.x] != *square)
+        .collect();
+    let mut changes = SpriteChanges {
+        moved: vec![],
+        spawned: vec![],
+        removed: vec![],
+    };
+
+    layout
+        .iter()
+        .enumerate()
+        .flat_map(|(y, rank)| {
+            rank.iter()
+                .enumerate()
+                .map(move |(x, square)| (Position { x, y }, *square))
+        })
+        .filter(|(position, square)| {
+            *square != Square::Empty
+                && !sprites
+                    .iter()
+                    .any(|(_, other, other_square)| other == position && other_square == square)
+        })
+        .for_each(|(position, square)| {
+            let closest = stale
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, _, other_square))| *other_square == square)
+                .min_by_key(|(_, (_, other, _))| {
+                    other.x.abs_diff(position.x) + other.y.abs_diff(position.y)
+                })
+                .map(|(index, _)| index);
+
+            match closest {
+                Some(index) => changes.moved.push((stale.swap_remove(index).0, position)),
+                None => changes.spawned.push((position, square)),
+            }
+        });
+
+    changes.removed = stale.into_iter().map(|(sprite, _, _)| *sprite).collect();
+    changes
+}
+
+/// Reconciles the piece sprites with the board layout whenever the board changes.
+///
+/// Sprites whose piece moved slide to their new square, unless they were
+/// dropped onto it, sprites whose piece was captured fade out, and sprites are
+/// spawned for any remaining pieces. When a new game starts, every sprite is
+/// replaced without animation.
+#[allow(clippy::too_many_arguments)]
+fn reconcile_pieces(
+    board: Res<Board>,
+    orientation: Res<Orientation>,
+    settings: Res<AnimationSettings>,
+    theme: Res<Theme>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    asset_server: Res<AssetServer>,
+    pieces: Query<(Entity, &Position, &Square, &Transform)>,
+    windows: Query<&Window>,
+    mut new_games: EventReader<NewGame>,
+) {
+    let new_game = new_games.read().count() > 0;
+    if !board.is_changed() {
+        return;
+    }
+
+    let sprites: Vec<_> = if new_game {
+        pieces
+            .iter()
+            .for_each(|(entity, ..)| commands.entity(entity).despawn_recursive());
+        vec![]
+    } else {
+        pieces
+            .iter()
+            .map(|(entity, position, square, _)| (entity, *position, *square))
+            .collect()
+    };
+    let changes = reconcile(&board, &sprites);
+
+    let window = windows.single();
+    let window_size = Vec2::new(window.width(), window.height());
+    let size = square_size(&board, window_size);
+
+    changes.moved.into_iter().for_each(|(entity, position)| {
+        let mut transform = *pieces.get(entity).unwrap().3;
+        let start = transform.translation.truncate();
+        let end = orientation.translation(&board, &position, size, window_size);
+
+        // A sprite dragged onto its new square is already there, so it snaps into place.
+        if start.distance(end) < size / 2.0 {
+            transform.translation = end.extend(0.5);
+            commands.entity(entity).insert((position, transform));
+        } else {
+            commands
+                .entity(entity)
+                .insert((position, Tween::new(start, &settings)));
+        }
+    });
+
+    // Captured pieces stop being pieces while they fade.
+    changes.removed.into_iter().for_each(|entity| {
+        commands
+            .entity(entity)
+            .remove::<(Position, Square)>()
+            .insert(FadeOut::new(&settings));
+    });
+
+    if changes.spawned.is_empty() {
+        return;
+    }
+
+    let mesh = meshes.add(Mesh::from(shape::Quad {
+        size: Vec2::splat(1.0),
+        flip: false,
+    }));
+
+    changes.spawned.into_iter().for_each(|(position, square)| {
+        let piece_texture = match square {
+            Square::Empty => return,
+            Square::Piece {
+                piece_colour,
+                piece_kind,
+            } => asset_server.load(texture_path(&theme.piece_set, &piece_colour, &piece_kind)),
+        };
+
+        commands
+            .spawn(MaterialMesh2dBundle {
+                mesh: mesh.clone().into(),
+                material: materials.add(ColorMaterial::from(piece_texture)),
+                transform: Transform::from_translation(
+                    orientation
+                        .translation(&board, &position, size, window_size)
+                        .extend(0.5),
+                )
+                .with_scale(Vec3::new(size, size, 1.0)),
+                ..default()
+            })
+            .insert((position, square));
+    });
+}
+
+/// Swaps the textures of the piece sprites when the piece set changes.
+fn retexture_pieces(
+    theme: Res<Theme>,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    pieces: Query<(&Square, &Handle<ColorMaterial>)>,
+) {
+    if !theme.is_changed() {
+        return;
+    }
+
+    pieces.iter().for_each(|(square, material)| {
+        if let (
+            Square::Piece {
+                piece_colour,
+                piece_kind,
+            },
+            Some(material),
+        ) = (square, materials.get_mut(material))
+        {
+            material.texture =
+                Some(asset_server.load(texture_path(&theme.piece_set, piece_colour, piece_kind)));
+        }
+    });
+}
@@ -0,0 +1,138 @@
+// Copyright (C) 2023  Brynley Llewellyn-Roux and Aryan Jassal
+//
+// This file is part of chess.
+//
+// chess is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published
+// by the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// chess is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A module for solving chess problems.
+//!
+//! The search is exhaustive, trying every valid move at every ply, so it is
+//! only practical for short problems.
+
+use crate::board::*;
+
+/// An enum representing what a chess problem asks for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stipulation {
+    /// The side to move forces checkmate against any defence.
+    Mate,
+    /// The side to move is checkmated, with both sides cooperating.
+    Helpmate,
+    /// The side to move forces the opponent to checkmate it against any defence.
+    Selfmate,
+}
+
+/// Returns every key of a problem, which is every first move that solves it.
+///
+/// Takes in the position, the stipulation, and the number of moves allowed to
+/// the side to move. More than one key means the problem is cooked.
+///
+/// # Examples
+///
+/// ```
+/// use chess::{
+///     board::{Board, Position},
+///     solver::{solve, Stipulation},
+/// };
+///
+/// let board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R3R1K1 w - - 0 1").unwrap();
+/// let keys = solve(&board, Stipulation::Mate, 1);
+/// assert_eq!(keys.len(), 2);
+/// assert!(keys.iter().all(|key| key.new_pos.y == 0));
+/// ```
+pub fn solve(board: &Board, stipulation: Stipulation, moves: usize) -> Vec<Move> {
+    if moves == 0 {
+        return vec![];
+    }
+
+    all_moves(board)
+        .into_iter()
+        .filter(|piece_move| {
+            let after = play(board, *piece_move);
+            match stipulation {
+                Stipulation::Mate => mates_after(&after, moves),
+                Stipulation::Helpmate => helpmates_after(&after, moves),
+                Stipulation::Selfmate => selfmates_after(&after, moves),
+            }
+        })
+        .collect()
+}
+
+/// Checks if the side that just moved has mated, or can still force mate in
+/// the moves it has left against every defence.
+fn mates_after(board: &Board, moves: usize) -> bool {
+    match board.outcome() {
+        Some(Outcome::Checkmate(_)) => true,
+        Some(_) => false,
+        None => {
+            moves > 1
+                && all_moves(board).into_iter().all(|defence| {
+                    let after = play(board, defence);
+                    after.outcome().is_none()
+                        && !solve(&after, Stipulation::Mate, moves - 1).is_empty()
+                })
+        }
+    }
+}
+
+/// Checks if, after the side to be mated has moved, its opponent can mate it
+/// now or the two sides can still cooperate to do so.
+fn helpmates_after(board: &Board, moves: usize) -> bool {
+    board.outcome().is_none()
+        && all_moves(board).into_iter().any(|reply| {
+            let after = play(board, reply);
+            match after.outcome() {
+                Some(Outcome::Checkmate(_)) => true,
+                Some(_) => false,
+                None => moves > 1 && !solve(&after, Stipulation::Helpmate, moves - 1).is_empty(),
+            }
+        })
+}
+
+/// Checks if, after the side to be mated has moved, every reply of its
+/// opponent mates it or still leaves it able to force a selfmate.
+fn selfmates_after(board: &Board, moves: usize) -> bool {
+    board.outcome().is_none()
+        && all_moves(board).into_iter().all(|reply| {
+            let after = play(board, reply);
+            match after.outcome() {
+                Some(Outcome::Checkmate(_)) => true,
+                Some(_) => false,
+                None => moves > 1 && !solve(&after, Stipulation::Selfmate, moves - 1).is_empty(),
+            }
+        })
+}
+
+/// Returns every valid move of the player to move.
+fn all_moves(board: &Board) -> Vec<Move> {
+    board
+        .layout()
+        .iter()
+        .enumerate()
+        .flat_map(|(y, rank)| {
+            rank.iter()
+                .enumerate()
+                .filter(|(_, square)| square.colour() == Some(board.player()))
+                .map(move |(x, _)| Position { x, y })
+        })
+        .flat_map(|position| board.valid_moves(&position))
+        .collect()
+}
+
+/// Returns the board after a move from `all_moves`, leaving the given board as it is.
+fn play(board: &Board, piece_move: Move) -> Board {
+    let mut after = board.clone();
+    after.make_valid_move(piece_move);
+    after
+}
@@ -0,0 +1,258 @@
+// Copyright (C) 2023  Brynley Llewellyn-Roux and Aryan Jassal
+//
+// This file is part of chess.
+//
+// chess is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published
+// by the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// chess is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A module for the sound plugin.
+//!
+//! The sounds are short chimes synthesised at runtime, so no audio assets are
+//! needed. Nothing is played if Bevy's `AudioPlugin` has not been added, which
+//! allows the game to run headless.
+
+use crate::{board::*, drag_and_drop::IllegalMove, game_state::GameState};
+use bevy::{
+    audio::{AddAudioSource, AudioPlugin, Decodable, Source, Volume},
+    prelude::*,
+};
+use std::{f32::consts::TAU, time::Duration};
+
+const SAMPLE_RATE: u32 = 44100;
+
+/// A plugin implementing sound effects.
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SoundSettings>()
+            .add_event::<SoundEffect>()
+            .add_systems(Update, detect_sounds)
+            .add_systems(Update, toggle_mute.run_if(in_state(GameState::InGame)))
+            .add_systems(OnEnter(GameState::GameOver), game_over_sound);
+
+        if app.is_plugin_added::<AudioPlugin>() {
+            app.add_audio_source::<Chime>()
+                .add_systems(PostUpdate, play_sounds);
+        }
+    }
+}
+
+/// An event for a sound effect to be played.
+///
+/// # Examples
+///
+/// ```
+/// use chess::sound::SoundEffect;
+///
+/// assert_eq!(SoundEffect::for_move("exd5"), SoundEffect::Capture);
+/// assert_eq!(SoundEffect::for_move("Qh5+"), SoundEffect::Check);
+/// assert_eq!(SoundEffect::for_move("Nf3"), SoundEffect::Move);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Event)]
+pub enum SoundEffect {
+    /// A piece was moved.
+    Move,
+    /// A piece was captured.
+    Capture,
+    /// A king castled.
+    Castle,
+    /// A king was put in check.
+    Check,
+    /// A move was rejected.
+    IllegalMove,
+    /// The game ended.
+    GameOver,
+}
+
+impl SoundEffect {
+    /// Returns the sound effect for a move written in Standard Algebraic Notation.
+    pub fn for_move(san: &str) -> Self {
+        if san.ends_with('+') || san.ends_with('#') {
+            SoundEffect::Check
+        } else if san.starts_with("O-O") {
+            SoundEffect::Castle
+        } else if san.contains('x') {
+            SoundEffect::Capture
+        } else {
+            SoundEffect::Move
+        }
+    }
+
+    /// Returns the notes of the sound effect, as frequencies in hertz and lengths in seconds.
+    fn notes(&self) -> Vec<(f32, f32)> {
+        match self {
+            SoundEffect::Move => vec![(523.25, 0.1)],
+            SoundEffect::Capture => vec![(392.0, 0.06), (261.63, 0.12)],
+            SoundEffect::Castle => vec![(523.25, 0.08), (523.25, 0.1)],
+            SoundEffect::Check => vec![(659.25, 0.08), (880.0, 0.15)],
+            SoundEffect::IllegalMove => vec![(164.81, 0.2)],
+            SoundEffect::GameOver => vec![(523.25, 0.15), (659.25, 0.15), (783.99, 0.4)],
+        }
+    }
+}
+
+/// A resource containing the sound settings.
+#[derive(Resource)]
+pub struct SoundSettings {
+    /// Whether sound effects are silenced.
+    pub muted: bool,
+    /// The volume of sound effects, from 0 to 1.
+    pub volume: f32,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            muted: false,
+            volume: 0.5,
+        }
+    }
+}
+
+impl SoundSettings {
+    /// Raises the volume by a quarter, going back down to a quarter after full volume.
+    pub fn next_volume(&mut self) {
+        self.volume = if self.volume >= 1.0 {
+            0.25
+        } else {
+            self.volume + 0.25
+        };
+    }
+}
+
+/// An audio source playing a sequence of decaying sine wave notes.
+#[derive(Asset, Clone, Debug, TypePath)]
+pub struct Chime {
+    notes: Vec<(f32, f32)>,
+}
+
+impl Decodable for Chime {
+    type DecoderItem = f32;
+    type Decoder = ChimeDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        ChimeDecoder {
+            notes: self.notes.clone(),
+            note: 0,
+            sample: 0,
+        }
+    }
+}
+
+/// The samples of a `Chime`.
+pub struct ChimeDecoder {
+    notes: Vec<(f32, f32)>,
+    note: usize,
+    sample: u32,
+}
+
+impl Iterator for ChimeDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (frequency, length) = *self.notes.get(self.note)?;
+
+        if self.sample as f32 >= length * SAMPLE_RATE as f32 {
+            self.note += 1;
+            self.sample = 0;
+            return self.next();
+        }
+
+        let time = self.sample as f32 / SAMPLE_RATE as f32;
+        self.sample += 1;
+
+        // A quick fade in avoids a click, and the decay makes the note ring.
+        let envelope = (time / 0.005).min(1.0) * (-5.0 * time / length).exp();
+        Some((TAU * frequency * time).sin() * envelope * 0.5)
+    }
+}
+
+impl Source for ChimeDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+fn detect_sounds(
+    board: Res<Board>,
+    mut ply: Local<usize>,
+    mut illegal_moves: EventReader<IllegalMove>,
+    mut sounds: EventWriter<SoundEffect>,
+) {
+    if illegal_moves.read().count() > 0 {
+        sounds.send(SoundEffect::IllegalMove);
+    }
+
+    if !board.is_changed() || board.ply() == *ply {
+        return;
+    }
+
+    // Moves that were undone, or a new game, make no more than a plain move sound.
+    let previous_ply = std::mem::replace(&mut *ply, board.ply());
+    if board.ply() < previous_ply {
+        if board.ply() > 0 {
+            sounds.send(SoundEffect::Move);
+        }
+        return;
+    }
+
+    // The game over sound is played instead when the move ended the game.
+    if board.outcome().is_none() {
+        if let Some(san) = board.san_list().get(board.ply() - 1) {
+            sounds.send(SoundEffect::for_move(san));
+        }
+    }
+}
+
+fn game_over_sound(mut sounds: EventWriter<SoundEffect>) {
+    sounds.send(SoundEffect::GameOver);
+}
+
+fn toggle_mute(keys: Res<Input<KeyCode>>, mut settings: ResMut<SoundSettings>) {
+    if keys.just_pressed(KeyCode::M) {
+        settings.muted = !settings.muted;
+    }
+}
+
+fn play_sounds(
+    settings: Res<SoundSettings>,
+    mut commands: Commands,
+    mut chimes: ResMut<Assets<Chime>>,
+    mut sounds: EventReader<SoundEffect>,
+) {
+    sounds.read().for_each(|sound| {
+        if settings.muted {
+            return;
+        }
+
+        commands.spawn(AudioSourceBundle {
+            source: chimes.add(Chime {
+                notes: sound.notes(),
+            }),
+            settings: PlaybackSettings::DESPAWN.with_volume(Volume::new_relative(settings.volume)),
+        });
+    });
+}
@@ -0,0 +1,774 @@
+// Copyright (C) 2023  Brynley Llewellyn-Roux and Aryan Jassal
+//
+// This file is part of chess.
+//
+// chess is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published
+// by the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// chess is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A module for generating and probing endgame tablebases.
+//!
+//! Tables are generated by retrograde analysis, working backwards from every
+//! checkmate, and hold the distance to mate of every position with their
+//! material. Only pawnless endings of up to four pieces are supported, as the
+//! board does not have pawn promotion yet.
+//!
+//! Positions are generated on plain lists of squares rather than on a `Board`,
+//! since a four piece table holds millions of them, but the moves
+//! follow the same rules.
+
+use crate::board::*;
+use std::io::{self, Read, Write};
+
+/// The most pieces a table can hold, counting both kings.
+const MAX_PIECES: usize = 4;
+
+/// The bytes every tablebase file starts with.
+const MAGIC: &[u8; 4] = b"CTB1";
+
+/// The steps a king takes, which are also the directions the sliding pieces move in.
+const KING_STEPS: [(i8, i8); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// The jumps a knight makes.
+const KNIGHT_STEPS: [(i8, i8); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+    (2, -1),
+    (2, 1),
+];
+
+/// An enum representing the distance to mate of a position.
+///
+/// Distances are counted in plies, from the point of view of the side to move.
+///
+/// # Examples
+///
+/// ```
+/// use chess::tablebase::Distance;
+///
+/// let distance = Distance::Win(3);
+/// match distance {
+///     Distance::Win(plies) => println!("Mate in {} moves.", (plies + 1) / 2),
+///     Distance::Loss(plies) => println!("Mated in {} moves.", plies / 2),
+///     Distance::Draw => println!("No side can force mate."),
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Distance {
+    /// The side to move mates in the given number of plies.
+    Win(u8),
+    /// The side to move is mated in the given number of plies.
+    Loss(u8),
+    /// Neither side can force mate.
+    Draw,
+}
+
+/// The squares of the pieces of a position, starting with the white and black
+/// kings. Tables with fewer pieces leave the last entries unused.
+type Squares = [usize; MAX_PIECES];
+
+/// A struct representing the endgame tablebase of a set of material.
+///
+/// Holds one byte per position, with either side to move. Positions that are
+/// mirror images or rotations of each other share their entry, and no two
+/// pieces ever share a square, so a four piece table takes about 3.5 MB.
+///
+/// # Examples
+///
+/// ```
+/// use chess::{
+///     board::Board,
+///     tablebase::{Distance, Tablebase},
+/// };
+///
+/// let tablebase = Tablebase::generate("KRK").unwrap();
+/// let board = Board::from_fen("7k/8/6K1/8/8/8/8/R7 w - - 0 1").unwrap();
+/// assert_eq!(tablebase.probe(&board), Some(Distance::Win(1)));
+/// ```
+pub struct Tablebase {
+    /// The numbering of the positions of the table.
+    indexer: Indexer,
+    /// The distance of every position, encoded by `encode`.
+    entries: Vec<u8>,
+}
+
+impl Tablebase {
+    /// Generates the tablebase of the given material.
+    ///
+    /// The material is written as the white pieces followed by the black
+    /// pieces, each starting with the king, such as `KQK` or `KRKN`. The
+    /// tables of every ending reachable by a capture are generated first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::tablebase::Tablebase;
+    ///
+    /// assert!(Tablebase::generate("KQK").is_ok());
+    /// assert!(Tablebase::generate("KPK").is_err());
+    /// ```
+    pub fn generate(material: &str) -> Result<Self, &'static str> {
+        Ok(Self::solve(parse_material(material)?))
+    }
+
+    /// Returns the distance to mate of a position, if its material matches the
+    /// table and the side not to move is not in check.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::{
+    ///     board::Board,
+    ///     tablebase::{Distance, Tablebase},
+    /// };
+    ///
+    /// let tablebase = Tablebase::generate("KQK").unwrap();
+    /// let board = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 b - - 0 1").unwrap();
+    /// assert!(matches!(tablebase.probe(&board), Some(Distance::Loss(_))));
+    /// assert_eq!(tablebase.probe(&Board::default()), None);
+    /// ```
+    pub fn probe(&self, board: &Board) -> Option<Distance> {
+        let mut pieces: Vec<(PieceColour, PieceKind, usize)> = board
+            .layout()
+            .iter()
+            .flatten()
+            .enumerate()
+            .filter_map(|(square, piece)| match piece {
+                Square::Piece {
+                    piece_colour,
+                    piece_kind,
+                } => Some((*piece_colour, *piece_kind, square)),
+                Square::Empty => None,
+            })
+            .collect();
+        if pieces.len() != self.indexer.kinds.len() {
+            return None;
+        }
+
+        // The pieces are put in the order of the table.
+        let mut squares = vec![];
+        for (colour, kind) in &self.indexer.kinds {
+            let found = pieces.iter().position(|(piece_colour, piece_kind, _)| {
+                piece_colour == colour && piece_kind == kind
+            })?;
+            squares.push(pieces.swap_remove(found).2);
+        }
+
+        let player = board.player();
+        is_legal(&self.indexer.kinds, &squares, player).then(|| self.distance(player, &squares))
+    }
+
+    /// Returns the name of the material of the table, such as `KBNK`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::tablebase::Tablebase;
+    ///
+    /// let tablebase = Tablebase::generate("KKR").unwrap();
+    /// assert_eq!(tablebase.material(), "KKR");
+    /// ```
+    pub fn material(&self) -> String {
+        [PieceColour::White, PieceColour::Black]
+            .iter()
+            .map(|colour| {
+                self.indexer
+                    .kinds
+                    .iter()
+                    .filter(|(piece_colour, _)| piece_colour == colour)
+                    .map(|(_, piece_kind)| letter(piece_kind))
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    /// Writes the table in its on-disk format.
+    ///
+    /// The format is a four byte magic number, the material as a length-prefixed
+    /// string, and then one byte per position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chess::tablebase::Tablebase;
+    ///
+    /// let mut bytes = vec![];
+    /// Tablebase::generate("KRK").unwrap().write_to(&mut bytes).unwrap();
+    /// let tablebase = Tablebase::read_from(bytes.as_slice()).unwrap();
+    /// assert_eq!(tablebase.material(), "KRK");
+    /// ```
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let material = self.material();
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[material.len() as u8])?;
+        writer.write_all(material.as_bytes())?;
+        writer.write_all(&self.entries)
+    }
+
+    /// Reads a table written by `write_to`.
+    ///
+    /// Returns an error if the data is not a complete table.
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let invalid = |err| io::Error::new(io::ErrorKind::InvalidData, err);
+
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("Error: Data is not a tablebase!"));
+        }
+
+        let mut length = [0];
+        reader.read_exact(&mut length)?;
+        let mut name = vec![0; length[0] as usize];
+        reader.read_exact(&mut name)?;
+        let material = String::from_utf8(name)
+            .map_err(|_| invalid("Error: Tablebase material is not valid text!"))
+            .and_then(|name| parse_material(&name).map_err(invalid))?;
+
+        let indexer = Indexer::new(&material);
+        let mut entries = vec![0; indexer.size()];
+        reader.read_exact(&mut entries)?;
+        Ok(Self { indexer, entries })
+    }
+
+    /// Returns the distance of a legal position, given the side to move and the
+    /// square of every piece in the order of the table.
+    fn distance(&self, player: &PieceColour, squares: &[usize]) -> Distance {
+        let canonical = self.indexer.canonical(squares);
+        decode(self.entries[self.indexer.index(player, &canonical[..squares.len()])])
+    }
+
+    /// Generates the table of the given material by retrograde analysis.
+    ///
+    /// Every position is first given its number of moves, and captures are
+    /// looked up in the smaller tables. Positions are then resolved in order of
+    /// their distance: a position is won once one move reaches a lost position,
+    /// and lost once every move reaches a won one.
+    fn solve(material: Vec<(PieceColour, PieceKind)>) -> Self {
+        // Every capture removes one of the pieces besides the kings.
+        let captures: Vec<Self> = (0..material.len())
+            .map(|captured| {
+                let mut remaining = material.clone();
+                remaining.remove(captured);
+                Self::solve(remaining)
+            })
+            .collect();
+
+        let indexer = Indexer::new(&material);
+        let kinds = &indexer.kinds;
+        let pieces = kinds.len();
+        let size = indexer.size();
+        let mut entries = vec![0; size];
+        let mut moves_left = vec![0u8; size];
+        let mut queued_win = vec![u8::MAX; size];
+        let mut pending: Vec<Vec<u32>> = vec![];
+
+        // Returns the distance of the position a move reaches for the opponent,
+        // which is a draw until that position is resolved.
+        let after = |entries: &[u8], player: &PieceColour, squares: &[usize], piece_move| {
+            let (moved, target) = piece_move;
+            let mut after = [0; MAX_PIECES];
+            after[..pieces].copy_from_slice(squares);
+            after[moved] = target;
+
+            let opponent = player.opponent();
+            match squares[2..].iter().position(|square| *square == target) {
+                Some(captured) => {
+                    let mut remaining = after[..pieces].to_vec();
+                    remaining.remove(captured + 2);
+                    captures[captured].distance(&opponent, &remaining)
+                }
+                None => {
+                    let canonical = indexer.canonical(&after[..pieces]);
+                    let position = indexer.index(&opponent, &canonical[..pieces]);
+                    decode(entries[position])
+                }
+            }
+        };
+
+        // Returns the distance of a position with moves, if every move reaches a won one.
+        let lost = |entries: &[u8], player: &PieceColour, squares: &[usize]| {
+            moves(kinds, squares, player).into_iter().try_fold(
+                0,
+                |longest, piece_move| match after(entries, player, squares, piece_move) {
+                    Distance::Win(plies) => Some(longest.max(plies as usize + 1)),
+                    _ => None,
+                },
+            )
+        };
+
+        for position in 0..size {
+            let (player, squares) = indexer.unindex(position);
+            let squares = &squares[..pieces];
+            if indexer.canonical(squares)[..pieces] != *squares
+                || !is_legal(kinds, squares, &player)
+            {
+                continue;
+            }
+
+            let valid = moves(kinds, squares, &player);
+            let mut drawn = false;
+            for &(moved, target) in &valid {
+                if !squares[2..].contains(&target) {
+                    moves_left[position] += 1;
+                    continue;
+                }
+                match after(&entries, &player, squares, (moved, target)) {
+                    Distance::Loss(plies) if plies + 1 < queued_win[position] => {
+                        queued_win[position] = plies + 1;
+                        queue(&mut pending, position, plies as usize + 1);
+                    }
+                    Distance::Loss(_) | Distance::Win(_) => (),
+                    Distance::Draw => drawn = true,
+                }
+            }
+
+            if drawn {
+                // A drawn capture is a move that never leads to a loss.
+                moves_left[position] = u8::MAX;
+            } else if indexer.is_symmetric(squares) {
+                // A symmetric position is reached back from fewer positions than
+                // it has moves, so it is checked again on every one.
+                moves_left[position] = moves_left[position].min(1);
+            }
+
+            let king = king_square(squares, &player);
+            if valid.is_empty() {
+                // Checkmate is a loss in no plies, and stalemate is left as a draw.
+                if is_attacked(kinds, squares, king, &player, None) {
+                    queue(&mut pending, position, 0);
+                }
+            } else if moves_left[position] == 0 && queued_win[position] == u8::MAX {
+                if let Some(plies) = lost(&entries, &player, squares) {
+                    queue(&mut pending, position, plies);
+                }
+            }
+        }
+
+        let mut plies = 0;
+        while plies < pending.len() {
+            for position in std::mem::take(&mut pending[plies]) {
+                let position = position as usize;
+                if entries[position] != 0 {
+                    continue;
+                }
+                entries[position] = encode(plies);
+
+                let (player, squares) = indexer.unindex(position);
+                for previous in unmoves(&indexer, &squares[..pieces], &player) {
+                    if entries[previous] != 0 {
+                        continue;
+                    }
+                    if plies % 2 == 0 {
+                        // A move into a lost position wins.
+                        if (plies + 1) < queued_win[previous] as usize {
+                            queued_win[previous] = (plies + 1) as u8;
+                            queue(&mut pending, previous, plies + 1);
+                        }
+                    } else if moves_left[previous] != u8::MAX {
+                        // Mirror images of a position are reached back from the
+                        // same one, so the count can run out before every move
+                        // is won, and the moves are checked once it does.
+                        moves_left[previous] = moves_left[previous].saturating_sub(1);
+                        if moves_left[previous] == 0 && queued_win[previous] == u8::MAX {
+                            let (mover, before) = indexer.unindex(previous);
+                            if let Some(longest) = lost(&entries, &mover, &before[..pieces]) {
+                                moves_left[previous] = u8::MAX;
+                                queue(&mut pending, previous, longest);
+                            }
+                        }
+                    }
+                }
+            }
+            plies += 1;
+        }
+
+        Self { indexer, entries }
+    }
+}
+
+/// A struct representing the numbering of the positions of a table.
+///
+/// Without pawns, the eight reflections and rotations of a position have the
+/// same distance, so only the one whose squares come first is numbered. The
+/// kings are numbered as a pair, and every other piece among the squares left
+/// empty by the pieces before it.
+struct Indexer {
+    /// The pieces of the table, starting with the white and black kings.
+    kinds: Vec<(PieceColour, PieceKind)>,
+    /// The placements of the kings that are numbered.
+    king_pairs: Vec<(usize, usize)>,
+    /// The number of every placement of the kings, by their squares.
+    king_pair_indices: Vec<Option<u32>>,
+}
+
+impl Indexer {
+    /// Creates the numbering of a table, given the pieces other than the kings.
+    fn new(material: &[(PieceColour, PieceKind)]) -> Self {
+        let kinds = [
+            (PieceColour::White, PieceKind::King),
+            (PieceColour::Black, PieceKind::King),
+        ]
+        .into_iter()
+        .chain(material.iter().copied())
+        .collect();
+
+        // The kings are never next to each other, which leaves 462 placements.
+        let mut king_pairs = vec![];
+        let mut king_pair_indices = vec![None; 64 * 64];
+        for white in 0..64usize {
+            for black in 0..64usize {
+                let apart =
+                    (white % 8).abs_diff(black % 8) > 1 || (white / 8).abs_diff(black / 8) > 1;
+                let first = (1..8).all(|symmetry| {
+                    (transform(white, symmetry), transform(black, symmetry)) >= (white, black)
+                });
+                if apart && first {
+                    king_pair_indices[white * 64 + black] = Some(king_pairs.len() as u32);
+                    king_pairs.push((white, black));
+                }
+            }
+        }
+
+        Self {
+            kinds,
+            king_pairs,
+            king_pair_indices,
+        }
+    }
+
+    /// Returns the number of positions in the table.
+    fn size(&self) -> usize {
+        (2..self.kinds.len()).fold(2 * self.king_pairs.len(), |size, i| size * (64 - i))
+    }
+
+    /// Returns the squares of a position after one of the eight symmetries of the
+    /// board, keeping identical pieces in the order of their squares.
+    fn transformed(&self, squares: &[usize], symmetry: usize) -> Squares {
+        let mut transformed = [0; MAX_PIECES];
+        for (square, original) in transformed.iter_mut().zip(squares) {
+            *square = transform(*original, symmetry);
+        }
+        // Each side has one king, so only the last two pieces can be identical.
+        if squares.len() == 4 && self.kinds[2] == self.kinds[3] {
+            transformed[2..4].sort_unstable();
+        }
+        transformed
+    }
+
+    /// Returns the numbered position among the symmetries of a position.
+    fn canonical(&self, squares: &[usize]) -> Squares {
+        (0..8)
+            .map(|symmetry| self.transformed(squares, symmetry))
+            .min()
+            .unwrap()
+    }
+
+    /// Checks if a position is its own image under a symmetry of the board.
+    fn is_symmetric(&self, squares: &[usize]) -> bool {
+        let identity = self.transformed(squares, 0);
+        (1..8).any(|symmetry| self.transformed(squares, symmetry) == identity)
+    }
+
+    /// Returns the index of a numbered position, given the side to move and the
+    /// square of every piece.
+    fn index(&self, player: &PieceColour, squares: &[usize]) -> usize {
+        let side = match player {
+            PieceColour::White => 0,
+            PieceColour::Black => 1,
+        };
+        let kings = self.king_pair_indices[squares[0] * 64 + squares[1]]
+            .expect("Error: Kings are not in their numbered placement!");
+        (2..squares.len()).fold(side * self.king_pairs.len() + kings as usize, |index, i| {
+            let taken = squares[..i]
+                .iter()
+                .filter(|square| **square < squares[i])
+                .count();
+            index * (64 - i) + squares[i] - taken
+        })
+    }
+
+    /// Returns the side to move and the square of every piece of a position.
+    fn unindex(&self, mut index: usize) -> (PieceColour, Squares) {
+        let pieces = self.kinds.len();
+        let mut squares = [0; MAX_PIECES];
+        for i in (2..pieces).rev() {
+            squares[i] = index % (64 - i);
+            index /= 64 - i;
+        }
+        (squares[0], squares[1]) = self.king_pairs[index % self.king_pairs.len()];
+
+        // Each piece skips over the squares taken by the pieces before it.
+        for i in 2..pieces {
+            let mut taken = squares;
+            taken[..i].sort_unstable();
+            for square in &taken[..i] {
+                if *square <= squares[i] {
+                    squares[i] += 1;
+                }
+            }
+        }
+
+        let player = match index / self.king_pairs.len() {
+            0 => PieceColour::White,
+            _ => PieceColour::Black,
+        };
+        (player, squares)
+    }
+}
+
+/// Adds a position to be resolved at the given distance.
+fn queue(pending: &mut Vec<Vec<u32>>, position: usize, plies: usize) {
+    if pending.len() <= plies {
+        pending.resize(plies + 1, vec![]);
+    }
+    pending[plies].push(position as u32);
+}
+
+/// Parses the name of a set of material, returning the pieces other than the kings.
+fn parse_material(material: &str) -> Result<Vec<(PieceColour, PieceKind)>, &'static str> {
+    let black_king = match material.strip_prefix('K').and_then(|rest| rest.find('K')) {
+        Some(found) => found + 1,
+        None => return Err("Error: Material must start each side with a king!"),
+    };
+    if material.len() > MAX_PIECES {
+        return Err("Error: Tablebases are limited to four pieces!");
+    }
+
+    material
+        .char_indices()
+        .filter(|(i, _)| *i != 0 && *i != black_king)
+        .map(|(i, c)| {
+            let piece_colour = if i < black_king {
+                PieceColour::White
+            } else {
+                PieceColour::Black
+            };
+            let piece_kind = match c {
+                'B' => PieceKind::Bishop,
+                'N' => PieceKind::Knight,
+                'Q' => PieceKind::Queen,
+                'R' => PieceKind::Rook,
+                'P' => return Err("Error: Tablebases with pawns need pawn promotion!"),
+                _ => return Err("Error: Material contains an invalid piece!"),
+            };
+            Ok((piece_colour, piece_kind))
+        })
+        .collect()
+}
+
+/// Returns the letter of a piece in the name of a set of material.
+fn letter(piece_kind: &PieceKind) -> char {
+    match piece_kind {
+        PieceKind::Bishop => 'B',
+        PieceKind::King => 'K',
+        PieceKind::Knight => 'N',
+        PieceKind::Pawn => 'P',
+        PieceKind::Queen => 'Q',
+        PieceKind::Rook => 'R',
+    }
+}
+
+/// Returns the image of a square under one of the eight symmetries of the
+/// board, which flip the files, the ranks and the diagonal in any combination.
+fn transform(square: usize, symmetry: usize) -> usize {
+    let (mut x, mut y) = (square % 8, square / 8);
+    if symmetry & 1 != 0 {
+        x = 7 - x;
+    }
+    if symmetry & 2 != 0 {
+        y = 7 - y;
+    }
+    if symmetry & 4 != 0 {
+        (x, y) = (y, x);
+    }
+    y * 8 + x
+}
+
+/// Encodes a resolved distance in plies, leaving zero for draws and illegal positions.
+///
+/// Lost positions always have an even distance, and won positions an odd one.
+fn encode(plies: usize) -> u8 {
+    u8::try_from(plies + 1).expect("Error: Distance to mate does not fit in a byte!")
+}
+
+/// Decodes an entry of a table.
+fn decode(entry: u8) -> Distance {
+    match entry {
+        0 => Distance::Draw,
+        entry if (entry - 1) % 2 == 0 => Distance::Loss(entry - 1),
+        entry => Distance::Win(entry - 1),
+    }
+}
+
+/// Returns the square of the king of the given colour.
+fn king_square(squares: &[usize], colour: &PieceColour) -> usize {
+    match colour {
+        PieceColour::White => squares[0],
+        PieceColour::Black => squares[1],
+    }
+}
+
+/// Returns the squares of a position as a bitmask.
+fn occupancy(squares: &[usize]) -> u64 {
+    squares
+        .iter()
+        .fold(0, |occupied, square| occupied | 1 << square)
+}
+
+/// Returns the squares a piece can reach as a bitmask, up to and including the
+/// first occupied square in each direction.
+fn reach(kind: &PieceKind, from: usize, occupied: u64) -> u64 {
+    let (steps, slides): (&[(i8, i8)], bool) = match kind {
+        PieceKind::King => (&KING_STEPS, false),
+        PieceKind::Knight => (&KNIGHT_STEPS, false),
+        PieceKind::Bishop => (&[(-1, -1), (-1, 1), (1, -1), (1, 1)], true),
+        PieceKind::Rook => (&[(-1, 0), (0, -1), (0, 1), (1, 0)], true),
+        PieceKind::Queen => (&KING_STEPS, true),
+        PieceKind::Pawn => (&[], false),
+    };
+
+    let mut targets = 0;
+    for (dx, dy) in steps {
+        let (mut x, mut y) = ((from % 8) as i8, (from / 8) as i8);
+        loop {
+            x += dx;
+            y += dy;
+            if !(0..8).contains(&x) || !(0..8).contains(&y) {
+                break;
+            }
+            let target = 1 << (y * 8 + x);
+            targets |= target;
+            if !slides || occupied & target != 0 {
+                break;
+            }
+        }
+    }
+    targets
+}
+
+/// Returns the squares set in a bitmask.
+fn bits(mut mask: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        let square = mask.trailing_zeros() as usize;
+        mask &= mask.wrapping_sub(1);
+        (square < 64).then_some(square)
+    })
+}
+
+/// Checks if a square is attacked by the pieces of the opponent of the given
+/// colour, leaving out a piece that has just been captured.
+fn is_attacked(
+    kinds: &[(PieceColour, PieceKind)],
+    squares: &[usize],
+    target: usize,
+    colour: &PieceColour,
+    captured: Option<usize>,
+) -> bool {
+    let occupied = occupancy(squares);
+    kinds
+        .iter()
+        .zip(squares)
+        .enumerate()
+        .any(|(i, ((piece_colour, piece_kind), square))| {
+            piece_colour != colour
+                && Some(i) != captured
+                && reach(piece_kind, *square, occupied) & 1 << target != 0
+        })
+}
+
+/// Checks if a position can occur: no two pieces share a square, and the side
+/// that just moved is not in check.
+fn is_legal(kinds: &[(PieceColour, PieceKind)], squares: &[usize], player: &PieceColour) -> bool {
+    let opponent = player.opponent();
+    occupancy(squares).count_ones() as usize == squares.len()
+        && !is_attacked(
+            kinds,
+            squares,
+            king_square(squares, &opponent),
+            &opponent,
+            None,
+        )
+}
+
+/// Returns the valid moves of the side to move, as the moved piece and its target square.
+fn moves(
+    kinds: &[(PieceColour, PieceKind)],
+    squares: &[usize],
+    player: &PieceColour,
+) -> Vec<(usize, usize)> {
+    let occupied = occupancy(squares);
+    let own = kinds
+        .iter()
+        .zip(squares)
+        .filter(|((colour, _), _)| colour == player)
+        .fold(0, |own, (_, square)| own | 1 << square);
+    // Kings are never captured, so their squares are left out as well.
+    let blocked = own | 1 << squares[0] | 1 << squares[1];
+
+    let mut valid = vec![];
+    let mut after = squares.to_vec();
+    for (moved, (colour, kind)) in kinds.iter().enumerate() {
+        if colour != player {
+            continue;
+        }
+        for target in bits(reach(kind, squares[moved], occupied) & !blocked) {
+            // A captured piece shares its square with the piece that took it.
+            let captured = squares.iter().position(|square| *square == target);
+            after[moved] = target;
+            let king = king_square(&after, player);
+            if !is_attacked(kinds, &after, king, player, captured) {
+                valid.push((moved, target));
+            }
+        }
+        after[moved] = squares[moved];
+    }
+    valid
+}
+
+/// Returns the index of every position that reaches the given one by a move
+/// without a capture.
+fn unmoves(indexer: &Indexer, squares: &[usize], player: &PieceColour) -> Vec<usize> {
+    let kinds = &indexer.kinds;
+    let mover = player.opponent();
+    let occupied = occupancy(squares);
+    let mut previous = vec![];
+    let mut before = squares.to_vec();
+    for (moved, (colour, kind)) in kinds.iter().enumerate() {
+        if *colour != mover {
+            continue;
+        }
+        // Every piece without pawns moves the same way backwards as forwards.
+        for origin in bits(reach(kind, squares[moved], occupied) & !occupied) {
+            before[moved] = origin;
+            if !is_attacked(kinds, &before, king_square(&before, player), player, None) {
+                let canonical = indexer.canonical(&before);
+                previous.push(indexer.index(&mover, &canonical[..squares.len()]));
+            }
+        }
+        before[moved] = squares[moved];
+    }
+    previous
+}
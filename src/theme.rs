@@ -0,0 +1,251 @@
+// Copyright (C) 2023  Brynley Llewellyn-Roux and Aryan Jassal
+//
+// This file is part of chess.
+//
+// chess is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published
+// by the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// chess is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A module for the theme plugin.
+//!
+//! The theme is saved to `chess/theme` in the user's configuration directory
+//! whenever it changes, and loaded again on startup.
+
+use crate::board::*;
+use bevy::{asset::io::file::FileAssetReader, prelude::*};
+use std::{env, fs, path::PathBuf};
+
+const DEFAULT_PIECE_SET: &str = "standard";
+
+/// A plugin implementing selectable board colours and piece sets.
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Theme::load())
+            .add_systems(Update, (recolour_board, save_theme));
+    }
+}
+
+/// An enum representing the colour schemes of the board.
+///
+/// # Examples
+///
+/// ```
+/// use chess::theme::BoardTheme;
+///
+/// let board_theme = BoardTheme::Green;
+/// assert_eq!(board_theme.next(), BoardTheme::Brown);
+/// assert_ne!(board_theme.light(), board_theme.dark());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BoardTheme {
+    /// Cream and green squares.
+    #[default]
+    Green,
+    /// Light and dark wood squares.
+    Brown,
+    /// Pale and slate blue squares.
+    Blue,
+    /// Light and dark grey squares.
+    Grey,
+}
+
+impl BoardTheme {
+    const ALL: [BoardTheme; 4] = [
+        BoardTheme::Green,
+        BoardTheme::Brown,
+        BoardTheme::Blue,
+        BoardTheme::Grey,
+    ];
+
+    /// Returns the colour of the light squares.
+    pub fn light(&self) -> Color {
+        match self {
+            BoardTheme::Green => Color::rgb(0.93, 0.93, 0.82),
+            BoardTheme::Brown => Color::rgb(0.94, 0.85, 0.71),
+            BoardTheme::Blue => Color::rgb(0.87, 0.89, 0.90),
+            BoardTheme::Grey => Color::rgb(0.85, 0.85, 0.85),
+        }
+    }
+
+    /// Returns the colour of the dark squares.
+    pub fn dark(&self) -> Color {
+        match self {
+            BoardTheme::Green => Color::rgb(0.46, 0.59, 0.34),
+            BoardTheme::Brown => Color::rgb(0.71, 0.53, 0.39),
+            BoardTheme::Blue => Color::rgb(0.55, 0.64, 0.68),
+            BoardTheme::Grey => Color::rgb(0.55, 0.55, 0.55),
+        }
+    }
+
+    /// Returns the colour of the square at a position.
+    pub fn square(&self, position: &Position) -> Color {
+        if (position.x + position.y).is_multiple_of(2) {
+            self.light()
+        } else {
+            self.dark()
+        }
+    }
+
+    /// Returns the next colour scheme, wrapping around to the first.
+    pub fn next(&self) -> Self {
+        let index = BoardTheme::ALL.iter().position(|theme| theme == self);
+        BoardTheme::ALL[(index.unwrap() + 1) % BoardTheme::ALL.len()]
+    }
+}
+
+/// A resource representing the look of the board and pieces.
+///
+/// # Examples
+///
+/// ```
+/// use chess::theme::{BoardTheme, Theme};
+///
+/// let theme = Theme::from_config("board = Blue\npieces = standard\n");
+/// assert_eq!(theme.board, BoardTheme::Blue);
+/// assert_eq!(Theme::from_config(&theme.to_config()), theme);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Resource)]
+pub struct Theme {
+    /// The colour scheme of the board.
+    pub board: BoardTheme,
+    /// The name of the piece set, which is a subdirectory of `assets/pieces`.
+    pub piece_set: String,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            board: BoardTheme::default(),
+            piece_set: DEFAULT_PIECE_SET.to_string(),
+        }
+    }
+}
+
+impl Theme {
+    /// Parses a theme from `key = value` lines, using the default for anything missing.
+    pub fn from_config(config: &str) -> Self {
+        let mut theme = Theme::default();
+
+        config
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .for_each(|(key, value)| match (key.trim(), value.trim()) {
+                ("board", value) => {
+                    if let Some(board) = BoardTheme::ALL
+                        .into_iter()
+                        .find(|board| format!("{:?}", board) == value)
+                    {
+                        theme.board = board;
+                    }
+                }
+                ("pieces", value) if !value.is_empty() => theme.piece_set = value.to_string(),
+                _ => (),
+            });
+
+        theme
+    }
+
+    /// Returns the theme as `key = value` lines.
+    pub fn to_config(&self) -> String {
+        format!("board = {:?}\npieces = {}\n", self.board, self.piece_set)
+    }
+
+    /// Switches to the next installed piece set.
+    pub fn next_piece_set(&mut self) {
+        let piece_sets = piece_sets();
+        if let Some(index) = piece_sets.iter().position(|set| *set == self.piece_set) {
+            self.piece_set = piece_sets[(index + 1) % piece_sets.len()].clone();
+        } else if let Some(set) = piece_sets.first() {
+            self.piece_set = set.clone();
+        }
+    }
+
+    fn load() -> Self {
+        let mut theme = config_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|config| Theme::from_config(&config))
+            .unwrap_or_default();
+
+        if !piece_sets().contains(&theme.piece_set) {
+            theme.piece_set = DEFAULT_PIECE_SET.to_string();
+        }
+
+        theme
+    }
+}
+
+/// Returns the names of the installed piece sets, in alphabetical order.
+pub fn piece_sets() -> Vec<String> {
+    let mut piece_sets: Vec<_> = fs::read_dir(
+        FileAssetReader::get_base_path()
+            .join("assets")
+            .join("pieces"),
+    )
+    .map(|entries| {
+        entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect()
+    })
+    .unwrap_or_default();
+
+    piece_sets.sort();
+    piece_sets
+}
+
+fn config_path() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|config| config.join("chess").join("theme"))
+}
+
+fn recolour_board(
+    theme: Res<Theme>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    squares: Query<(&Position, &Handle<ColorMaterial>), Without<Square>>,
+) {
+    if !theme.is_changed() {
+        return;
+    }
+
+    squares.iter().for_each(|(position, material)| {
+        if let Some(material) = materials.get_mut(material) {
+            material.color = theme.board.square(position);
+        }
+    });
+}
+
+fn save_theme(theme: Res<Theme>) {
+    if !theme.is_changed() || theme.is_added() {
+        return;
+    }
+
+    let Some(path) = config_path() else {
+        return;
+    };
+
+    if let Err(err) = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, theme.to_config()))
+    {
+        eprintln!(
+            "Error: Could not save the theme to {}: {}!",
+            path.display(),
+            err
+        );
+    }
+}
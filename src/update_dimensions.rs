@@ -17,10 +17,10 @@
 
 //! A module for the update dimensions plugin.
 
-use crate::board::*;
+use crate::{board::*, orientation::Orientation};
 use bevy::{prelude::*, window::WindowResized};
 
-/// A plugin implementing rescaling of pieces on the chessboard.
+/// A plugin implementing rescaling and positioning of pieces on the chessboard.
 pub struct UpdateDimensionsPlugin;
 
 impl Plugin for UpdateDimensionsPlugin {
@@ -29,23 +29,115 @@ impl Plugin for UpdateDimensionsPlugin {
     }
 }
 
+/// A component representing a coordinate label drawn along the edge of the chessboard.
+///
+/// Labels are drawn in the corner of the square they belong to, so they follow the
+/// orientation of the board.
+#[derive(Clone, Copy, Component, Debug)]
+pub enum CoordinateLabel {
+    /// A file label, containing the x-coordinate of its file.
+    File(usize),
+    /// A rank label, containing the y-coordinate of its rank.
+    Rank(usize),
+}
+
+/// A component for interface nodes placed just to the right of the chessboard.
+#[derive(Clone, Copy, Component, Debug)]
+pub struct BesideBoard;
+
+/// The width of the side panels: the move list, and the nodes placed beside the chessboard.
+pub const SIDE_PANEL_WIDTH: f32 = 220.0;
+
+/// The gap between the chessboard and the nodes placed beside it, and between the panels.
+const BESIDE_BOARD_MARGIN: f32 = 15.0;
+
+/// The width kept free to the right of the chessboard, so the side panels never overlap.
+const SIDE_PANELS_WIDTH: f32 = 2.0 * SIDE_PANEL_WIDTH + 3.0 * BESIDE_BOARD_MARGIN;
+
+/// Returns the size of a square when the chessboard fills as much of the window as it can.
+///
+/// Room is left to the right of the chessboard for the side panels.
+///
+/// # Examples
+///
+/// ```
+/// use bevy::prelude::Vec2;
+/// use chess::{board::Board, update_dimensions::square_size};
+///
+/// let board = Board::default();
+/// assert_eq!(square_size(&board, Vec2::new(1920.0, 1080.0)), 135.0);
+/// assert_eq!(square_size(&board, Vec2::new(1024.0, 768.0)), 67.375);
+/// ```
+pub fn square_size(board: &Board, window_size: Vec2) -> f32 {
+    let squares = board.layout().len() as f32;
+    ((window_size.x - SIDE_PANELS_WIDTH) / squares)
+        .min(window_size.y / squares)
+        .max(0.0)
+}
+
 fn update_dimensions(
     board: Res<Board>,
+    orientation: Res<Orientation>,
     mut query: Query<(&mut Transform, &Position)>,
+    mut labels: Query<(&mut Transform, &mut Text, &CoordinateLabel), Without<Position>>,
+    mut beside_board: Query<&mut Style, With<BesideBoard>>,
     mut resize_events: EventReader<WindowResized>,
+    windows: Query<&Window>,
 ) {
-    resize_events.read().for_each(|event| {
-        let new_size = (event.width / board.layout().len() as f32)
-            .min(event.height / board.layout().len() as f32);
-
-        query.iter_mut().for_each(|(mut transform, position)| {
-            transform.scale = Vec3::splat(new_size);
-
-            transform.translation = Vec3::new(
-                position.x as f32 * new_size - event.width / 2.0 + (new_size / 2.0),
-                position.y as f32 * new_size - event.height / 2.0 + (new_size / 2.0),
-                transform.translation.z,
-            )
-        });
+    if resize_events.read().count() == 0 && !orientation.is_changed() {
+        return;
+    }
+
+    let window = windows.single();
+    let window_size = Vec2::new(window.width(), window.height());
+    let new_size = square_size(&board, window_size);
+
+    query.iter_mut().for_each(|(mut transform, position)| {
+        // Depth is left unscaled so that children keep their layering.
+        transform.scale = Vec3::new(new_size, new_size, 1.0);
+
+        transform.translation = orientation
+            .translation(&board, position, new_size, window_size)
+            .extend(transform.translation.z);
     });
+
+    let last = board.layout().len() - 1;
+    let (bottom_rank, left_file) = match orientation.bottom {
+        PieceColour::White => (last, 0),
+        PieceColour::Black => (0, last),
+    };
+    let padding = new_size * 0.05;
+
+    labels
+        .iter_mut()
+        .for_each(|(mut transform, mut text, label)| {
+            let (position, offset) = match label {
+                CoordinateLabel::File(x) => (
+                    Position {
+                        x: *x,
+                        y: bottom_rank,
+                    },
+                    Vec2::new(new_size / 2.0 - padding, -new_size / 2.0 + padding),
+                ),
+                CoordinateLabel::Rank(y) => (
+                    Position {
+                        x: left_file,
+                        y: *y,
+                    },
+                    Vec2::new(-new_size / 2.0 + padding, new_size / 2.0 - padding),
+                ),
+            };
+
+            text.sections
+                .iter_mut()
+                .for_each(|section| section.style.font_size = new_size * 0.2);
+            transform.translation =
+                (orientation.translation(&board, &position, new_size, window_size) + offset)
+                    .extend(transform.translation.z);
+        });
+
+    let board_width = board.layout().len() as f32 * new_size;
+    beside_board
+        .iter_mut()
+        .for_each(|mut style| style.left = Val::Px(board_width + BESIDE_BOARD_MARGIN));
 }
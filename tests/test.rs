@@ -15,7 +15,38 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use chess::board::{Board, Move, PieceColour, PieceKind, Position, Square};
+use bevy::prelude::*;
+use chess::{
+    board::{Board, Move, Outcome, PieceColour, PieceKind, Position, Square},
+    clock::{Clock, Period, TimeControl},
+    drag_and_drop::IllegalMove,
+    game_state::GameState,
+    pieces::reconcile,
+    solver::{solve, Stipulation},
+    sound::{SoundEffect, SoundPlugin},
+    tablebase::{Distance, Tablebase},
+    theme::{BoardTheme, Theme},
+};
+use std::time::Duration;
+
+type Coordinates = (usize, usize);
+
+fn play(board: &mut Board, moves: &[(Coordinates, Coordinates)]) {
+    moves.iter().for_each(|((old_x, old_y), (new_x, new_y))| {
+        board
+            .move_piece(Move {
+                old_pos: Position {
+                    x: *old_x,
+                    y: *old_y,
+                },
+                new_pos: Position {
+                    x: *new_x,
+                    y: *new_y,
+                },
+            })
+            .unwrap()
+    });
+}
 
 #[test]
 fn piece_move() {
@@ -210,3 +241,556 @@ fn piece_move() {
         ]
     )
 }
+
+#[test]
+fn valid_moves() {
+    let board = Board::default();
+
+    let destinations: Vec<(usize, usize)> = board
+        .valid_moves(&Position { x: 4, y: 6 })
+        .iter()
+        .map(|piece_move| (piece_move.new_pos.x, piece_move.new_pos.y))
+        .collect();
+    assert_eq!(destinations, vec![(4, 4), (4, 5)]);
+
+    assert!(board.valid_moves(&Position { x: 4, y: 1 }).is_empty());
+    assert!(board.valid_moves(&Position { x: 4, y: 4 }).is_empty());
+}
+
+#[test]
+fn check() {
+    let mut layout = vec![vec![Square::Empty; 8]; 8];
+    layout[7][4] = Square::Piece {
+        piece_kind: PieceKind::King,
+        piece_colour: PieceColour::White,
+    };
+    layout[0][4] = Square::Piece {
+        piece_kind: PieceKind::Rook,
+        piece_colour: PieceColour::Black,
+    };
+    layout[5][4] = Square::Piece {
+        piece_kind: PieceKind::Pawn,
+        piece_colour: PieceColour::White,
+    };
+    let mut board = Board::new(layout, vec![], PieceColour::White);
+    assert!(!board.is_check(&PieceColour::White));
+
+    board
+        .move_piece(Move {
+            old_pos: Position { x: 4, y: 5 },
+            new_pos: Position { x: 4, y: 4 },
+        })
+        .unwrap();
+    assert!(!board.is_check(&PieceColour::White));
+
+    board
+        .move_piece(Move {
+            old_pos: Position { x: 4, y: 0 },
+            new_pos: Position { x: 3, y: 0 },
+        })
+        .unwrap();
+    assert!(!board.is_check(&PieceColour::White));
+
+    board
+        .move_piece(Move {
+            old_pos: Position { x: 4, y: 4 },
+            new_pos: Position { x: 4, y: 3 },
+        })
+        .unwrap();
+    board
+        .move_piece(Move {
+            old_pos: Position { x: 3, y: 0 },
+            new_pos: Position { x: 3, y: 7 },
+        })
+        .unwrap();
+    assert!(board.is_check(&PieceColour::White));
+    assert!(!board.is_check(&PieceColour::Black));
+}
+
+#[test]
+fn checkmate() {
+    let mut board = Board::default();
+    play(
+        &mut board,
+        &[
+            ((5, 6), (5, 5)),
+            ((4, 1), (4, 3)),
+            ((6, 6), (6, 4)),
+            ((3, 0), (7, 4)),
+        ],
+    );
+
+    assert_eq!(board.san_list().last().unwrap(), "Qh4#");
+    assert_eq!(
+        board.outcome(),
+        Some(Outcome::Checkmate(PieceColour::Black))
+    );
+}
+
+#[test]
+fn stalemate() {
+    let board = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+    assert_eq!(board.outcome(), Some(Outcome::Stalemate));
+}
+
+#[test]
+fn self_check() {
+    let mut board = Board::from_fen("4r2k/8/8/8/8/8/4B3/4K3 w - - 0 1").unwrap();
+    assert!(board
+        .move_piece(Move {
+            old_pos: Position { x: 4, y: 6 },
+            new_pos: Position { x: 3, y: 5 },
+        })
+        .is_err());
+}
+
+#[test]
+fn fen() {
+    let board =
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    assert_eq!(board.layout(), Board::default().layout());
+    assert_eq!(board.player(), &PieceColour::White);
+
+    assert!(Board::from_fen("").is_err());
+    assert!(Board::from_fen("8/8/8/8/8/8/8 w - - 0 1").is_err());
+    assert!(Board::from_fen("9/8/8/8/8/8/8/8 w - - 0 1").is_err());
+    assert!(Board::from_fen("x7/8/8/8/8/8/8/8 w - - 0 1").is_err());
+    assert!(Board::from_fen("4k3/8/8/8/8/8/8/4K3 x - - 0 1").is_err());
+    assert!(Board::from_fen("8/8/8/8/8/8/8/4K3 w - - 0 1").is_err());
+    assert!(Board::from_fen("4k3/8/8/8/8/8/8/3KK3 w - - 0 1").is_err());
+    assert!(Board::from_fen("4k3/8/8/8/8/8/8/4RK2 w - - 0 1").is_err());
+}
+
+#[test]
+fn solver() {
+    // Both rooks mate on the back rank, so the problem is cooked.
+    let board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R3R1K1 w - - 0 1").unwrap();
+    let keys = solve(&board, Stipulation::Mate, 1);
+    assert_eq!(keys.len(), 2);
+
+    let board = Board::from_fen("7k/8/6K1/8/8/8/8/R7 b - - 0 1").unwrap();
+    let keys = solve(&board, Stipulation::Helpmate, 1);
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0].new_pos, Position { x: 6, y: 0 });
+    assert!(solve(&board, Stipulation::Mate, 1).is_empty());
+
+    let board = Board::from_fen("8/8/3R1B2/2p5/K1k1P3/8/5Qr1/5n2 w - - 0 1").unwrap();
+    let keys = solve(&board, Stipulation::Selfmate, 1);
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0].new_pos, Position { x: 0, y: 6 });
+}
+
+#[test]
+fn tablebase() {
+    let tablebase = Tablebase::generate("KQK").unwrap();
+    let mut board = Board::from_fen("8/8/8/3k4/8/8/8/KQ6 w - - 0 1").unwrap();
+    let Some(Distance::Win(plies)) = tablebase.probe(&board) else {
+        panic!("KQK should be won for the side with the queen");
+    };
+
+    // Both sides follow the table, and the board must agree on every step.
+    for remaining in (0..plies).rev() {
+        let children: Vec<(Move, Distance)> = (0..8)
+            .flat_map(|y| (0..8).map(move |x| Position { x, y }))
+            .flat_map(|position| board.valid_moves(&position))
+            .map(|piece_move| {
+                let mut after = board.clone();
+                after.move_piece(piece_move).unwrap();
+                (piece_move, tablebase.probe(&after).unwrap())
+            })
+            .collect();
+        let expected = if remaining % 2 == 0 {
+            Distance::Loss(remaining)
+        } else {
+            Distance::Win(remaining)
+        };
+        let (piece_move, _) = children
+            .iter()
+            .find(|(_, distance)| *distance == expected)
+            .unwrap();
+        board.move_piece(*piece_move).unwrap();
+    }
+    assert_eq!(
+        board.outcome(),
+        Some(Outcome::Checkmate(PieceColour::White))
+    );
+
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/3NK3 w - - 0 1").unwrap();
+    assert_eq!(
+        Tablebase::generate("KNK").unwrap().probe(&board),
+        Some(Distance::Draw)
+    );
+    assert_eq!(tablebase.probe(&board), None);
+
+    // Mirror images share an entry, so only 462 placements of the kings are kept.
+    let mut bytes = vec![];
+    tablebase.write_to(&mut bytes).unwrap();
+    assert_eq!(bytes.len(), 8 + 2 * 462 * 62);
+    let read = Tablebase::read_from(bytes.as_slice()).unwrap();
+    assert_eq!(read.probe(&board), None);
+    assert!(Tablebase::read_from(&bytes[..bytes.len() - 1]).is_err());
+
+    assert!(Tablebase::generate("KPK").is_err());
+    assert!(Tablebase::generate("KQRKR").is_err());
+    assert!(Tablebase::generate("QK").is_err());
+}
+
+#[test]
+fn tablebase_capture() {
+    // Only taking the knight wins fastest, which the table finds through KRK.
+    let tablebase = Tablebase::generate("KRKN").unwrap();
+    let board = Board::from_fen("K1k3nR/8/8/8/8/8/8/8 w - - 0 1").unwrap();
+    assert_eq!(tablebase.probe(&board), Some(Distance::Win(23)));
+
+    let mut after = board.clone();
+    after
+        .move_piece(Move {
+            old_pos: Position { x: 7, y: 0 },
+            new_pos: Position { x: 6, y: 0 },
+        })
+        .unwrap();
+    assert_eq!(
+        Tablebase::generate("KRK").unwrap().probe(&after),
+        Some(Distance::Loss(22))
+    );
+    assert_eq!(tablebase.probe(&after), None);
+}
+
+#[test]
+fn blocked_double_push() {
+    // A pawn cannot capture straight ahead by moving two squares.
+    let mut board = Board::from_fen("4k3/8/8/8/4p3/8/4P3/4K3 w - - 0 1").unwrap();
+    assert!(board
+        .move_piece(Move {
+            old_pos: Position { x: 4, y: 6 },
+            new_pos: Position { x: 4, y: 4 },
+        })
+        .is_err());
+    assert!(board
+        .move_piece(Move {
+            old_pos: Position { x: 4, y: 6 },
+            new_pos: Position { x: 4, y: 5 },
+        })
+        .is_ok());
+
+    let mut board = Board::from_fen("4k3/4p3/8/4P3/8/8/8/4K3 b - - 0 1").unwrap();
+    assert!(board
+        .move_piece(Move {
+            old_pos: Position { x: 4, y: 1 },
+            new_pos: Position { x: 4, y: 3 },
+        })
+        .is_err());
+}
+
+#[test]
+fn san() {
+    let mut board = Board::default();
+    play(
+        &mut board,
+        &[((4, 6), (4, 4)), ((3, 1), (3, 3)), ((4, 4), (3, 3))],
+    );
+    assert_eq!(board.san_list(), vec!["e4", "d5", "exd5"]);
+
+    let mut layout = vec![vec![Square::Empty; 8]; 8];
+    layout[7][0] = Square::Piece {
+        piece_kind: PieceKind::Rook,
+        piece_colour: PieceColour::White,
+    };
+    layout[7][7] = Square::Piece {
+        piece_kind: PieceKind::Rook,
+        piece_colour: PieceColour::White,
+    };
+    layout[0][3] = Square::Piece {
+        piece_kind: PieceKind::King,
+        piece_colour: PieceColour::Black,
+    };
+    let board = Board::new(layout, vec![], PieceColour::White);
+    assert_eq!(
+        board.to_san(&Move {
+            old_pos: Position { x: 0, y: 7 },
+            new_pos: Position { x: 3, y: 7 },
+        }),
+        "Rad1+"
+    );
+}
+
+#[test]
+fn undo_redo() {
+    let mut board = Board::default();
+    assert!(board.undo().is_err());
+
+    board
+        .move_piece(Move {
+            old_pos: Position { x: 6, y: 7 },
+            new_pos: Position { x: 5, y: 5 },
+        })
+        .unwrap();
+    board.undo().unwrap();
+    assert_eq!(board.layout(), Board::default().layout());
+    assert_eq!(board.player(), &PieceColour::White);
+    assert_eq!(board.ply(), 0);
+    assert_eq!(board.san_list(), vec!["Nf3"]);
+
+    board.redo().unwrap();
+    assert_eq!(board.player(), &PieceColour::Black);
+    assert!(board.redo().is_err());
+
+    board.undo().unwrap();
+    board
+        .move_piece(Move {
+            old_pos: Position { x: 1, y: 7 },
+            new_pos: Position { x: 2, y: 5 },
+        })
+        .unwrap();
+    assert_eq!(board.san_list(), vec!["Nc3"]);
+    assert!(board.redo().is_err());
+}
+
+#[test]
+fn captured_pieces() {
+    let mut board = Board::default();
+    play(
+        &mut board,
+        &[((4, 6), (4, 4)), ((3, 1), (3, 3)), ((4, 4), (3, 3))],
+    );
+
+    assert_eq!(
+        board.captured_pieces(&PieceColour::Black),
+        vec![PieceKind::Pawn]
+    );
+    assert!(board.captured_pieces(&PieceColour::White).is_empty());
+    assert_eq!(
+        board.material(&PieceColour::White) - board.material(&PieceColour::Black),
+        1
+    );
+
+    board.undo().unwrap();
+    assert!(board.captured_pieces(&PieceColour::Black).is_empty());
+}
+
+#[test]
+fn reconcile_sprites() {
+    let mut board = Board::from_fen("n3k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+    let a1 = Position { x: 0, y: 7 };
+    let a8 = Position { x: 0, y: 0 };
+    let rook = board.layout()[7][0];
+    let knight = board.layout()[0][0];
+    let sprites = vec![
+        (0, a8, knight),
+        (1, Position { x: 4, y: 0 }, board.layout()[0][4]),
+        (2, a1, rook),
+        (3, Position { x: 4, y: 7 }, board.layout()[7][4]),
+    ];
+
+    let changes = reconcile(&board, &sprites);
+    assert!(changes.moved.is_empty());
+    assert!(changes.spawned.is_empty());
+    assert!(changes.removed.is_empty());
+
+    board
+        .move_piece(Move {
+            old_pos: a1,
+            new_pos: a8,
+        })
+        .unwrap();
+    let changes = reconcile(&board, &sprites);
+    assert_eq!(changes.moved, vec![(2, a8)]);
+    assert!(changes.spawned.is_empty());
+    assert_eq!(changes.removed, vec![0]);
+
+    let sprites = vec![
+        (1, Position { x: 4, y: 0 }, board.layout()[0][4]),
+        (2, a8, rook),
+        (3, Position { x: 4, y: 7 }, board.layout()[7][4]),
+    ];
+    board.undo().unwrap();
+    let changes = reconcile(&board, &sprites);
+    assert_eq!(changes.moved, vec![(2, a1)]);
+    assert_eq!(changes.spawned, vec![(a8, knight)]);
+    assert!(changes.removed.is_empty());
+}
+
+#[test]
+fn theme_config() {
+    let theme = Theme::from_config("board = Brown\npieces = classic\n");
+    assert_eq!(theme.board, BoardTheme::Brown);
+    assert_eq!(theme.piece_set, "classic");
+    assert_eq!(Theme::from_config(&theme.to_config()), theme);
+
+    assert_eq!(Theme::from_config(""), Theme::default());
+    assert_eq!(
+        Theme::from_config("board = Purple\npieces =\nnonsense"),
+        Theme::default()
+    );
+}
+
+#[test]
+fn sound_effects() {
+    // Without Bevy's audio plugin the sound effects are chosen but never played.
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_state::<GameState>()
+        .add_event::<IllegalMove>()
+        .init_resource::<Board>()
+        .add_plugins(SoundPlugin);
+    app.update();
+
+    app.world
+        .resource_mut::<Board>()
+        .move_piece(Move {
+            old_pos: Position { x: 4, y: 6 },
+            new_pos: Position { x: 4, y: 4 },
+        })
+        .unwrap();
+    app.world.send_event(IllegalMove);
+    app.update();
+
+    let events = app.world.resource::<Events<SoundEffect>>();
+    let sounds: Vec<_> = events.get_reader().read(events).copied().collect();
+    assert_eq!(sounds, vec![SoundEffect::IllegalMove, SoundEffect::Move]);
+
+    assert_eq!(SoundEffect::for_move("Bxf7+"), SoundEffect::Check);
+    assert_eq!(SoundEffect::for_move("O-O-O"), SoundEffect::Castle);
+    assert_eq!(SoundEffect::for_move("Nxe5"), SoundEffect::Capture);
+}
+
+#[test]
+fn clock_increments() {
+    let secs = Duration::from_secs;
+    let white = PieceColour::White;
+    let black = PieceColour::Black;
+
+    let mut clock = Clock::new(TimeControl::Fischer {
+        base: secs(60),
+        increment: secs(5),
+    });
+    clock.tick(&white, secs(10));
+    clock.press(&white, 1);
+    assert_eq!(clock.remaining(&white), Some(secs(55)));
+    assert_eq!(clock.remaining(&black), Some(secs(60)));
+
+    let mut clock = Clock::new(TimeControl::Bronstein {
+        base: secs(60),
+        delay: secs(5),
+    });
+    clock.tick(&white, secs(3));
+    clock.press(&white, 1);
+    assert_eq!(clock.remaining(&white), Some(secs(60)));
+    clock.tick(&black, secs(8));
+    clock.press(&black, 2);
+    assert_eq!(clock.remaining(&black), Some(secs(57)));
+
+    let mut clock = Clock::new(TimeControl::UsDelay {
+        base: secs(60),
+        delay: secs(5),
+    });
+    clock.tick(&white, secs(3));
+    clock.tick(&white, secs(4));
+    clock.press(&white, 1);
+    assert_eq!(clock.remaining(&white), Some(secs(58)));
+
+    let mut clock = Clock::new(TimeControl::Hourglass { base: secs(60) });
+    clock.tick(&white, secs(10));
+    assert_eq!(clock.remaining(&white), Some(secs(50)));
+    assert_eq!(clock.remaining(&black), Some(secs(70)));
+
+    let clock = Clock::new(TimeControl::Untimed);
+    assert_eq!(clock.remaining(&white), None);
+}
+
+#[test]
+fn clock_periods() {
+    let secs = Duration::from_secs;
+    let white = PieceColour::White;
+    let mut clock = Clock::new(TimeControl::MultiPeriod {
+        periods: vec![
+            Period {
+                moves: Some(2),
+                time: secs(60),
+            },
+            Period {
+                moves: None,
+                time: secs(30),
+            },
+        ],
+        increment: secs(1),
+    });
+
+    clock.tick(&white, secs(10));
+    clock.press(&white, 1);
+    assert_eq!(clock.remaining(&white), Some(secs(51)));
+    clock.tick(&white, secs(10));
+    clock.press(&white, 3);
+    assert_eq!(clock.remaining(&white), Some(secs(72)));
+    clock.tick(&white, secs(10));
+    clock.press(&white, 5);
+    assert_eq!(clock.remaining(&white), Some(secs(63)));
+}
+
+#[test]
+fn clock_history() {
+    let secs = Duration::from_secs;
+    let white = PieceColour::White;
+    let black = PieceColour::Black;
+    let mut board = Board::default();
+    let mut clock = Clock::new(TimeControl::Fischer {
+        base: secs(60),
+        increment: secs(5),
+    });
+
+    clock.tick(&white, secs(10));
+    play(&mut board, &[((4, 6), (4, 4))]);
+    clock.update(&board);
+    clock.tick(&black, secs(20));
+    play(&mut board, &[((3, 1), (3, 3))]);
+    clock.update(&board);
+    assert_eq!(clock.remaining(&white), Some(secs(55)));
+    assert_eq!(clock.remaining(&black), Some(secs(45)));
+
+    // Going back and forth through the moves does not add the increments again.
+    board.undo().unwrap();
+    board.undo().unwrap();
+    clock.update(&board);
+    assert_eq!(clock.remaining(&white), Some(secs(60)));
+    assert_eq!(clock.remaining(&black), Some(secs(60)));
+    board.redo().unwrap();
+    board.redo().unwrap();
+    clock.update(&board);
+    assert_eq!(clock.remaining(&white), Some(secs(55)));
+    assert_eq!(clock.remaining(&black), Some(secs(45)));
+
+    // A different move after an undo is pressed as a new move.
+    board.undo().unwrap();
+    clock.update(&board);
+    clock.tick(&black, secs(3));
+    play(&mut board, &[((4, 1), (4, 3))]);
+    clock.update(&board);
+    assert_eq!(clock.remaining(&black), Some(secs(62)));
+}
+
+#[test]
+fn flag_fall() {
+    let mut clock = Clock::new(TimeControl::Fischer {
+        base: Duration::from_secs(1),
+        increment: Duration::ZERO,
+    });
+    clock.tick(&PieceColour::White, Duration::from_secs(2));
+    assert_eq!(clock.flagged(), Some(&PieceColour::White));
+    assert_eq!(clock.remaining(&PieceColour::White), Some(Duration::ZERO));
+
+    assert_eq!(
+        clock.outcome(&Board::default()),
+        Some(Outcome::Timeout(PieceColour::Black))
+    );
+
+    let board = Board::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+    assert_eq!(clock.outcome(&board), Some(Outcome::TimeoutDraw));
+
+    let board = Board::from_fen("4k3/8/8/8/8/8/4P3/1n2K3 w - - 0 1").unwrap();
+    assert_eq!(
+        clock.outcome(&board),
+        Some(Outcome::Timeout(PieceColour::Black))
+    );
+
+    assert_eq!(Clock::new(TimeControl::Untimed).outcome(&board), None);
+}